@@ -0,0 +1,45 @@
+use std::fmt;
+
+pub const GROUP_CODE_MIN_LEN: usize = 4;
+pub const GROUP_CODE_MAX_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupCodeError {
+    TooShort,
+    TooLong,
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for GroupCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(
+                f,
+                "Group code needs at least {} characters",
+                GROUP_CODE_MIN_LEN
+            ),
+            Self::TooLong => write!(
+                f,
+                "Group code can be at most {} characters",
+                GROUP_CODE_MAX_LEN
+            ),
+            Self::InvalidCharacter(c) => write!(f, "Group code can't contain '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for GroupCodeError {}
+
+// A group is just the code its players share, so there is nothing to create first.
+// Returns the code as it's stored: trimmed and uppercase.
+pub fn group_code(code: &str) -> Result<String, GroupCodeError> {
+    let code = code.trim().to_ascii_uppercase();
+    if let Some(c) = code.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(GroupCodeError::InvalidCharacter(c));
+    }
+    match code.len() {
+        len if len < GROUP_CODE_MIN_LEN => Err(GroupCodeError::TooShort),
+        len if len > GROUP_CODE_MAX_LEN => Err(GroupCodeError::TooLong),
+        _ => Ok(code),
+    }
+}
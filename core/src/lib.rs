@@ -0,0 +1,101 @@
+mod group;
+mod name;
+mod power_up;
+mod race;
+mod replay;
+mod run;
+mod scoring;
+mod weed;
+
+pub use group::{GROUP_CODE_MAX_LEN, GROUP_CODE_MIN_LEN, GroupCodeError, group_code};
+pub use name::{NAME_MAX_LEN, NAME_MIN_LEN, NameError, NameRules};
+pub use power_up::{CLEAR_WEEDS, FREEZE_MS, POWER_UP_CHANCE, PowerUp};
+pub use race::RaceMessage;
+pub use replay::{InputLog, Replay, ReplayError, replay};
+pub use run::{Outcome, Press, Run};
+pub use scoring::{ScoreOrder, Scoring};
+pub use weed::{MAX_SPREADS, WeedType};
+
+use serde::Deserialize;
+
+pub const WRONG_KEY_PENALTY_MS: u32 = 1000;
+
+// Logged for keys that can't be part of any prompt, they still count as wrong
+pub const UNKNOWN_KEY: char = '\u{fffd}';
+// Every key of a chord has to be pressed within this long of the first
+pub const CHORD_WINDOW_MS: u32 = 250;
+
+// The keys one of a level's letters asks for. A single character is one key, capitals,
+// digits and punctuation included. Keys joined with '+' are a chord, "a+;" is a and
+// semicolon together. Anything else is its first character, as it always was.
+pub fn prompt_keys(prompt: &str) -> Vec<char> {
+    if prompt.chars().count() <= 1 {
+        return prompt.chars().collect();
+    }
+    prompt
+        .split('+')
+        .filter_map(|key| key.chars().next())
+        .collect()
+}
+
+// The gameplay half of a level definition in levels.json
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct LevelRules {
+    pub letters: Vec<String>,
+    // Seconds allowed for each prompt
+    pub time: f32,
+    // Weeds to clear
+    pub count: u32,
+    // Wrong keys that lose the level
+    pub fail_count: u32,
+    #[serde(default)]
+    pub scoring: Scoring,
+    // The kinds of weed that can appear, picked at random for each weed
+    #[serde(default)]
+    pub weeds: Vec<WeedType>,
+    // The power-ups golden letters can give, none when left out
+    #[serde(default)]
+    pub power_ups: Vec<PowerUp>,
+}
+
+impl LevelRules {
+    pub fn time_limit_ms(&self) -> u32 {
+        (self.time * 1000.0).round() as u32
+    }
+
+    // The longest the level could take: every prompt, spreads and tough weeds included,
+    // run to the end of its timer and frozen along the way
+    pub fn max_time_ms(&self) -> u32 {
+        let hits = self
+            .weeds
+            .iter()
+            .map(|weed| weed.hits)
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        let prompts = (self.count + 1 + MAX_SPREADS).saturating_mul(hits);
+        prompts.saturating_mul(self.time_limit_ms().saturating_add(FREEZE_MS))
+    }
+
+    pub fn par_time_ms(&self) -> u32 {
+        match self.scoring.par_time {
+            Some(par_time) => (par_time * 1000.0).round() as u32,
+            // Every weed's prompt plus the last one
+            None => (self.count + 1) * self.time_limit_ms() / 2,
+        }
+    }
+}
+
+// Correct keys answered this quickly keep a streak going, a slower one or a wrong key
+// starts it over
+pub const STREAK_WINDOW_MS: u32 = 1500;
+// Every this many streak letters adds one to the multiplier
+pub const STREAK_STEP: u32 = 5;
+pub const MAX_STREAK_MULTIPLIER: u32 = 4;
+// Taken off the score for each streak letter, times the multiplier at the time, unless
+// the level's scoring says otherwise
+pub const STREAK_BONUS_MS: u32 = 100;
+
+pub fn streak_multiplier(streak: u32) -> u32 {
+    (1 + streak / STREAK_STEP).min(MAX_STREAK_MULTIPLIER)
+}
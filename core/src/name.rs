@@ -0,0 +1,196 @@
+use std::fmt;
+
+pub const NAME_MIN_LEN: usize = 2;
+pub const NAME_MAX_LEN: usize = 16;
+
+const DEFAULT_BLOCKLIST: &str = include_str!("blocklist.txt");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    TooShort,
+    TooLong,
+    InvalidCharacter(char),
+    Blocked,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "Name needs at least {} characters", NAME_MIN_LEN),
+            Self::TooLong => write!(f, "Name can be at most {} characters", NAME_MAX_LEN),
+            Self::InvalidCharacter(c) => write!(f, "Name can't contain '{}'", c),
+            Self::Blocked => write!(f, "That name isn't allowed"),
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+fn allowed(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')
+}
+
+// Lowercase letters only, with the usual number and symbol stand-ins swapped back
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter_map(|c| match c.to_ascii_lowercase() {
+            '0' => Some('o'),
+            '1' | '!' => Some('i'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' => Some('t'),
+            c if c.is_ascii_lowercase() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+// The name's words, split at spaces and symbols and where a capital starts a new one in
+// camelCase, then normalized
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut previous = None;
+    for c in name.chars() {
+        if matches!(c, ' ' | '-' | '_' | '.') {
+            words.push(String::new());
+        } else {
+            if c.is_ascii_uppercase() && previous.is_some_and(|p: char| p.is_ascii_lowercase()) {
+                words.push(String::new());
+            }
+            if let Some(word) = words.last_mut() {
+                word.push(c);
+            }
+        }
+        previous = Some(c);
+    }
+    words
+        .iter()
+        .map(|word| normalize(word))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+// A blocked word, or with a trailing * in the list a stem blocking every word it starts
+#[derive(Debug, Clone)]
+struct Blocked {
+    word: String,
+    stem: bool,
+}
+
+impl Blocked {
+    fn blocks(&self, word: &str) -> bool {
+        match self.stem {
+            true => word.starts_with(self.word.as_str()),
+            false => word == self.word || word.strip_suffix('s') == Some(self.word.as_str()),
+        }
+    }
+}
+
+// Player name rules shared by the menu and the leaderboard
+#[derive(Debug, Clone)]
+pub struct NameRules {
+    blocklist: Vec<Blocked>,
+}
+
+impl Default for NameRules {
+    fn default() -> Self {
+        Self::new().with_blocklist(DEFAULT_BLOCKLIST)
+    }
+}
+
+impl NameRules {
+    // No blocked words at all
+    pub fn new() -> Self {
+        Self { blocklist: vec![] }
+    }
+
+    // Adds one word per line, blank lines and # comments are skipped
+    pub fn with_blocklist(mut self, words: &str) -> Self {
+        self.blocklist.extend(
+            words
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| Blocked {
+                    word: normalize(line),
+                    stem: line.ends_with('*'),
+                })
+                .filter(|blocked| !blocked.word.is_empty()),
+        );
+        self
+    }
+
+    // Returns the name as it should be stored: trimmed, with runs of spaces collapsed
+    pub fn validate(&self, name: &str) -> Result<String, NameError> {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if let Some(c) = name.chars().find(|&c| !allowed(c)) {
+            return Err(NameError::InvalidCharacter(c));
+        }
+        if name.len() < NAME_MIN_LEN {
+            return Err(NameError::TooShort);
+        }
+        if name.len() > NAME_MAX_LEN {
+            return Err(NameError::TooLong);
+        }
+
+        // Whole words only, so a word inside an ordinary one doesn't count. The words run
+        // together are checked as well, for one spelled out with spaces.
+        let mut words = words(&name);
+        words.push(words.concat());
+        if words
+            .iter()
+            .any(|word| self.blocklist.iter().any(|blocked| blocked.blocks(word)))
+        {
+            return Err(NameError::Blocked);
+        }
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_whole_words() {
+        let rules = NameRules::default();
+        for name in [
+            "puta",
+            "Big Cock",
+            "BigCock",
+            "c0ck_69",
+            "the.nazis",
+            "f u c k",
+            "fuckface",
+            "PendejoKing",
+        ] {
+            assert_eq!(rules.validate(name), Err(NameError::Blocked), "{}", name);
+        }
+    }
+
+    #[test]
+    fn allows_words_containing_blocked_ones() {
+        let rules = NameRules::default();
+        for name in [
+            "Computation",
+            "Therapist",
+            "Hancock",
+            "Peacock",
+            "Scunthorpe",
+            "Grape Juice",
+            "Cocktail",
+        ] {
+            assert_eq!(rules.validate(name), Ok(name.to_string()));
+        }
+    }
+
+    #[test]
+    fn extra_words_and_stems() {
+        let rules = NameRules::new().with_blocklist("# extra\nweed\nsprout*\n");
+        assert_eq!(rules.validate("Weeds"), Err(NameError::Blocked));
+        assert_eq!(rules.validate("Sprouting"), Err(NameError::Blocked));
+        assert_eq!(rules.validate("Tumbleweed"), Ok("Tumbleweed".to_string()));
+    }
+}
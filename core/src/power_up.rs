@@ -0,0 +1,34 @@
+use serde::Deserialize;
+
+// Effects granted by typing a golden letter. A level lists the ones it can give in its
+// "power_ups" in levels.json, a level without any never shows a golden letter.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerUp {
+    FreezeTimer,
+    ClearWeeds,
+    RestoreHealth,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 3] = [
+        PowerUp::FreezeTimer,
+        PowerUp::ClearWeeds,
+        PowerUp::RestoreHealth,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::FreezeTimer => "Freeze",
+            Self::ClearWeeds => "Clear",
+            Self::RestoreHealth => "Heal",
+        }
+    }
+}
+
+// Chance of any prompt being golden
+pub const POWER_UP_CHANCE: f64 = 0.1;
+// The letter timer doesn't count down for this long
+pub const FREEZE_MS: u32 = 3000;
+// Weeds cleared by the golden letter, its own included
+pub const CLEAR_WEEDS: u32 = 3;
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+// Messages between the game and the relay server for a networked 1v1 race, sent as
+// JSON text frames. The relay pairs the first two players to join a room, picks the
+// seed and passes everything else on to the opponent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RaceMessage {
+    // Game to relay. The first player in the room picks the level.
+    Join {
+        room: String,
+        name: String,
+        level: usize,
+    },
+    // Relay to both players once the room is full
+    Start {
+        level: usize,
+        seed: u64,
+        opponent: String,
+    },
+    // Relay to a third player trying to join
+    RoomFull,
+    // Relay to the remaining player when the other disconnects
+    OpponentLeft,
+    // Game to opponent after every key press
+    Progress {
+        weeds_left: u32,
+        fail_count: u32,
+    },
+    // Game to opponent when their run is over
+    Finished {
+        complete: bool,
+        time_ms: u32,
+        score: u32,
+    },
+}
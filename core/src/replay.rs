@@ -0,0 +1,227 @@
+use crate::{LevelRules, Outcome, Run};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Every key pressed during a run and when, in milliseconds since the level started.
+// Keys that can't be in a prompt are logged as UNKNOWN_KEY, they still count as wrong.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct InputLog {
+    pub keys: String,
+    pub times: Vec<u32>,
+}
+
+impl InputLog {
+    pub fn push(&mut self, at: u32, key: char) {
+        self.keys.push(key);
+        self.times.push(at);
+    }
+
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub score: u32,
+    pub time_ms: u32,
+    pub fail_count: u32,
+    pub prompt_times: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    MismatchedLog,
+    OutOfOrder,
+    // Keys logged later than the level could possibly last
+    TooLong,
+    InputAfterEnd,
+    NotFinished(Outcome),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MismatchedLog => write!(f, "every key needs a time"),
+            Self::OutOfOrder => write!(f, "key times go backwards"),
+            Self::TooLong => write!(f, "keys pressed after the level's longest time"),
+            Self::InputAfterEnd => write!(f, "keys pressed after the level ended"),
+            Self::NotFinished(Outcome::TimedOut) => write!(f, "a prompt timed out"),
+            Self::NotFinished(Outcome::TooManyWrongKeys) => write!(f, "too many wrong keys"),
+            Self::NotFinished(_) => write!(f, "the level was not finished"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+// Plays the logged keys through the same rules the game used
+pub fn replay(rules: LevelRules, seed: u64, log: &InputLog) -> Result<Replay, ReplayError> {
+    if log.keys.chars().count() != log.times.len() {
+        return Err(ReplayError::MismatchedLog);
+    }
+    if log.times.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(ReplayError::OutOfOrder);
+    }
+    if log
+        .times
+        .last()
+        .is_some_and(|&last| last > rules.max_time_ms())
+    {
+        return Err(ReplayError::TooLong);
+    }
+
+    let mut run = Run::new(rules, seed);
+    for (key, &at) in log.keys.chars().zip(&log.times) {
+        if !run.is_playing() {
+            return Err(ReplayError::InputAfterEnd);
+        }
+        run.press(at, key);
+    }
+
+    match run.outcome() {
+        Outcome::Complete => Ok(Replay {
+            score: run.score(),
+            time_ms: run.elapsed_ms(),
+            fail_count: run.fail_count(),
+            prompt_times: run.prompt_times().to_vec(),
+        }),
+        outcome => Err(ReplayError::NotFinished(outcome)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MAX_SPREADS, WeedType, prompt_keys};
+
+    fn rules() -> LevelRules {
+        LevelRules {
+            letters: ["a", "s", "d", "f"].map(String::from).to_vec(),
+            time: 2.0,
+            count: 5,
+            fail_count: 3,
+            ..Default::default()
+        }
+    }
+
+    // Answers every prompt `step` milliseconds after it appears
+    fn play(rules: LevelRules, seed: u64, step: u32) -> (Run, InputLog) {
+        let mut run = Run::new(rules, seed);
+        let mut log = InputLog::default();
+        let mut now = 0;
+        while let Some(prompt) = run.prompt().map(str::to_string) {
+            now += step;
+            for key in prompt_keys(&prompt) {
+                run.press(now, key);
+                log.push(now, key);
+            }
+        }
+        (run, log)
+    }
+
+    #[test]
+    fn replays_the_same_score() {
+        let (run, log) = play(rules(), 7, 400);
+        assert_eq!(run.outcome(), Outcome::Complete);
+
+        let first = replay(rules(), 7, &log).unwrap();
+        assert_eq!(first.score, run.score());
+        assert_eq!(first.time_ms, run.elapsed_ms());
+        assert_eq!(replay(rules(), 7, &log).unwrap(), first);
+    }
+
+    #[test]
+    fn another_seed_asks_for_other_keys() {
+        let (_, log) = play(rules(), 7, 400);
+        let seed = (0..100)
+            .find(|&seed| Run::new(rules(), seed).prompt() != Run::new(rules(), 7).prompt())
+            .unwrap();
+        assert!(replay(rules(), seed, &log).is_err());
+    }
+
+    #[test]
+    fn rejects_a_key_without_a_time() {
+        let log = InputLog {
+            keys: "as".into(),
+            times: vec![100],
+        };
+        assert_eq!(replay(rules(), 7, &log), Err(ReplayError::MismatchedLog));
+    }
+
+    #[test]
+    fn rejects_times_going_backwards() {
+        let (_, mut log) = play(rules(), 7, 400);
+        log.times.swap(0, 1);
+        assert_eq!(replay(rules(), 7, &log), Err(ReplayError::OutOfOrder));
+    }
+
+    #[test]
+    fn rejects_times_past_the_longest_level() {
+        let (_, mut log) = play(rules(), 7, 400);
+        log.times.pop();
+        log.times.push(4_000_000_000);
+        assert_eq!(replay(rules(), 7, &log), Err(ReplayError::TooLong));
+    }
+
+    #[test]
+    fn spreaders_stop_spreading() {
+        let rules = LevelRules {
+            weeds: vec![WeedType {
+                spreads: true,
+                ..Default::default()
+            }],
+            ..rules()
+        };
+        let mut run = Run::new(rules.clone(), 7);
+        assert_eq!(run.tick(u32::MAX), Outcome::TimedOut);
+        assert_eq!(run.spread_count(), MAX_SPREADS);
+
+        let log = InputLog {
+            keys: "a".into(),
+            times: vec![rules.max_time_ms()],
+        };
+        assert_eq!(
+            replay(rules, 7, &log),
+            Err(ReplayError::NotFinished(Outcome::TimedOut))
+        );
+    }
+
+    #[test]
+    fn rejects_keys_after_the_end() {
+        let (_, mut log) = play(rules(), 7, 400);
+        let last = *log.times.last().unwrap();
+        log.push(last + 100, 'a');
+        assert_eq!(replay(rules(), 7, &log), Err(ReplayError::InputAfterEnd));
+    }
+
+    #[test]
+    fn rejects_unfinished_runs() {
+        let (_, mut log) = play(rules(), 7, 400);
+        log.keys.pop();
+        log.times.pop();
+        assert_eq!(
+            replay(rules(), 7, &log),
+            Err(ReplayError::NotFinished(Outcome::Playing))
+        );
+
+        let (_, log) = play(rules(), 7, 2500);
+        assert_eq!(
+            replay(rules(), 7, &log),
+            Err(ReplayError::NotFinished(Outcome::TimedOut))
+        );
+
+        let wrong = InputLog {
+            keys: "zzz".into(),
+            times: vec![100, 200, 300],
+        };
+        assert_eq!(
+            replay(rules(), 7, &wrong),
+            Err(ReplayError::NotFinished(Outcome::TooManyWrongKeys))
+        );
+    }
+}
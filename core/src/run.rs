@@ -0,0 +1,323 @@
+use crate::{
+    CHORD_WINDOW_MS, CLEAR_WEEDS, FREEZE_MS, LevelRules, MAX_SPREADS, POWER_UP_CHANCE, PowerUp,
+    STREAK_WINDOW_MS, WeedType, prompt_keys, streak_multiplier,
+};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Playing,
+    Complete,
+    TimedOut,
+    TooManyWrongKeys,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Press {
+    Correct,
+    // One key of a chord, the rest are still to come
+    Held,
+    Wrong,
+    // The run was already over
+    Ignored,
+}
+
+// One attempt at a level. Time is in whole milliseconds since the level started so
+// the game and a replay of its input log make exactly the same decisions.
+#[derive(Debug, Clone)]
+pub struct Run {
+    rules: LevelRules,
+    rng: StdRng,
+    prompt: Option<String>,
+    prompt_started: u32,
+    held: Vec<char>,
+    held_since: u32,
+    prompt_times: Vec<u32>,
+    now: u32,
+    weeds_left: u32,
+    fail_count: u32,
+    streak: u32,
+    best_streak: u32,
+    streak_bonus_ms: u32,
+    weed: WeedType,
+    hits_left: u32,
+    spread_count: u32,
+    golden: Option<PowerUp>,
+    frozen_until: u32,
+    power_ups: Vec<(PowerUp, u32)>,
+    outcome: Outcome,
+}
+
+impl Run {
+    pub fn new(rules: LevelRules, seed: u64) -> Self {
+        let mut run = Self {
+            weeds_left: rules.count,
+            rules,
+            rng: StdRng::seed_from_u64(seed),
+            prompt: None,
+            prompt_started: 0,
+            held: vec![],
+            held_since: 0,
+            prompt_times: vec![],
+            now: 0,
+            fail_count: 0,
+            streak: 0,
+            best_streak: 0,
+            streak_bonus_ms: 0,
+            weed: WeedType::default(),
+            hits_left: 1,
+            spread_count: 0,
+            golden: None,
+            frozen_until: 0,
+            power_ups: vec![],
+            outcome: Outcome::Playing,
+        };
+        run.next_weed();
+        run.next_prompt();
+        run
+    }
+
+    // Levels without weed types don't touch the rng here, so their seeds still give
+    // the same prompts
+    fn next_weed(&mut self) {
+        if let Some(weed) = self.rules.weeds.choose(&mut self.rng) {
+            self.weed = weed.clone();
+        }
+        self.hits_left = self.weed.hits.max(1);
+    }
+
+    fn next_prompt(&mut self) {
+        self.next_prompt_at(self.now);
+    }
+
+    fn next_prompt_at(&mut self, started: u32) {
+        self.prompt = self
+            .rules
+            .letters
+            .choose(&mut self.rng)
+            .filter(|s| !s.is_empty())
+            .cloned();
+        self.prompt_started = started;
+        self.held.clear();
+        // Levels without power-ups don't roll for them, so their seeds still give the
+        // same prompts
+        self.golden = None;
+        if !self.rules.power_ups.is_empty() && self.rng.random_bool(POWER_UP_CHANCE) {
+            self.golden = self.rules.power_ups.choose(&mut self.rng).copied();
+        }
+    }
+
+    // The prompt's timer only starts once a freeze is over
+    fn deadline(&self) -> u32 {
+        self.prompt_started.max(self.frozen_until) + self.rules.time_limit_ms()
+    }
+
+    fn use_power_up(&mut self, power_up: PowerUp) {
+        match power_up {
+            PowerUp::FreezeTimer => self.frozen_until = self.now + FREEZE_MS,
+            // The golden letter's own weed is cleared as usual
+            PowerUp::ClearWeeds => {
+                self.weeds_left -= (CLEAR_WEEDS - 1).min(self.weeds_left);
+                self.hits_left = 1;
+            }
+            PowerUp::RestoreHealth => self.fail_count = self.fail_count.saturating_sub(1),
+        }
+        self.power_ups.push((power_up, self.now));
+    }
+
+    pub fn rules(&self) -> &LevelRules {
+        &self.rules
+    }
+
+    pub fn prompt(&self) -> Option<&str> {
+        self.prompt.as_deref()
+    }
+
+    // The chord keys pressed so far
+    pub fn held(&self) -> &[char] {
+        &self.held
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.outcome == Outcome::Playing
+    }
+
+    pub fn weeds_left(&self) -> u32 {
+        self.weeds_left
+    }
+
+    pub fn fail_count(&self) -> u32 {
+        self.fail_count
+    }
+
+    // The weed the prompt belongs to
+    pub fn weed(&self) -> &WeedType {
+        &self.weed
+    }
+
+    // Correct letters still needed to clear the current weed
+    pub fn hits_left(&self) -> u32 {
+        self.hits_left
+    }
+
+    // The power-up typing the prompt would give
+    pub fn golden(&self) -> Option<PowerUp> {
+        self.golden
+    }
+
+    // Milliseconds of freeze left on the letter timer
+    pub fn frozen_ms(&self) -> u32 {
+        self.frozen_until.saturating_sub(self.now)
+    }
+
+    // Every power-up given and when
+    pub fn power_ups(&self) -> &[(PowerUp, u32)] {
+        &self.power_ups
+    }
+
+    // Extra weeds grown by spreaders running out of time
+    pub fn spread_count(&self) -> u32 {
+        self.spread_count
+    }
+
+    // Fast correct keys in a row
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    pub fn best_streak(&self) -> u32 {
+        self.best_streak
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        streak_multiplier(self.streak)
+    }
+
+    pub fn streak_bonus_ms(&self) -> u32 {
+        self.streak_bonus_ms
+    }
+
+    // Milliseconds from each prompt appearing to its correct key
+    pub fn prompt_times(&self) -> &[u32] {
+        &self.prompt_times
+    }
+
+    pub fn elapsed_ms(&self) -> u32 {
+        self.now
+    }
+
+    pub fn time_left_ms(&self) -> u32 {
+        self.deadline().saturating_sub(self.now)
+    }
+
+    // Weighted time and penalties less the streak bonus, lower is better
+    pub fn cost(&self) -> u32 {
+        self.rules
+            .scoring
+            .cost(self.now, self.fail_count, self.streak_bonus_ms)
+    }
+
+    // The cost in the level's scoring order
+    pub fn score(&self) -> u32 {
+        self.rules
+            .scoring
+            .score(self.cost(), self.rules.par_time_ms())
+    }
+
+    // Out of three, none unless the level was completed
+    pub fn stars(&self) -> u8 {
+        match self.outcome {
+            Outcome::Complete => self
+                .rules
+                .scoring
+                .stars(self.cost(), self.rules.par_time_ms()),
+            _ => 0,
+        }
+    }
+
+    // Moves the clock forward and times out the prompt if its timer ran out. A
+    // spreader grows another weed instead and gets a new letter from the moment its
+    // timer ran out, so a replay that only ticks on key presses spreads the same way.
+    // Once the level has spread MAX_SPREADS times it times out as usual.
+    pub fn tick(&mut self, now: u32) -> Outcome {
+        if self.is_playing() {
+            self.now = self.now.max(now);
+            while self.is_playing() && self.now > self.deadline() {
+                if self.weed.spreads && self.spread_count < MAX_SPREADS {
+                    self.weeds_left += 1;
+                    self.spread_count += 1;
+                    self.next_prompt_at(self.deadline() + 1);
+                } else {
+                    self.prompt = None;
+                    self.outcome = Outcome::TimedOut;
+                }
+            }
+        }
+        self.outcome
+    }
+
+    pub fn press(&mut self, now: u32, key: char) -> Press {
+        if self.tick(now) != Outcome::Playing {
+            return Press::Ignored;
+        }
+
+        let keys = self.prompt.as_deref().map(prompt_keys).unwrap_or_default();
+        if keys.contains(&key) && !self.held.contains(&key) {
+            // A chord starts over when its keys are too far apart
+            if !self.held.is_empty() && self.now - self.held_since > CHORD_WINDOW_MS {
+                self.held.clear();
+            }
+            if self.held.is_empty() {
+                self.held_since = self.now;
+            }
+            self.held.push(key);
+            if self.held.len() < keys.len() {
+                return Press::Held;
+            }
+            self.held.clear();
+
+            let prompt_time = self.now - self.prompt_started;
+            self.prompt_times.push(prompt_time);
+            if prompt_time <= STREAK_WINDOW_MS {
+                self.streak += 1;
+                self.best_streak = self.best_streak.max(self.streak);
+                self.streak_bonus_ms += self.rules.scoring.streak_bonus_ms * self.multiplier();
+            } else {
+                self.streak = 0;
+            }
+            if let Some(power_up) = self.golden {
+                self.use_power_up(power_up);
+            }
+            // A tough weed takes another letter. The last weed is gone once the
+            // prompt after it is answered.
+            self.hits_left -= 1;
+            if self.hits_left > 0 {
+                self.next_prompt();
+            } else if self.weeds_left == 0 {
+                self.prompt = None;
+                self.outcome = Outcome::Complete;
+            } else {
+                self.weeds_left -= 1;
+                self.next_weed();
+                self.next_prompt();
+            }
+            Press::Correct
+        } else {
+            // A wrong key wacks a weed but grows a permanent one in its place
+            self.held.clear();
+            self.fail_count += self.weed.miss_cost;
+            self.streak = 0;
+            if self.fail_count >= self.rules.fail_count {
+                self.prompt = None;
+                self.outcome = Outcome::TooManyWrongKeys;
+            }
+            Press::Wrong
+        }
+    }
+}
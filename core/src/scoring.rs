@@ -0,0 +1,97 @@
+use crate::{STREAK_BONUS_MS, WRONG_KEY_PENALTY_MS};
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreOrder {
+    #[default]
+    Lower,
+    Higher,
+}
+
+impl ScoreOrder {
+    // Best score first, for sorting leaderboards
+    pub fn compare(self, a: u32, b: u32) -> Ordering {
+        match self {
+            Self::Lower => a.cmp(&b),
+            Self::Higher => b.cmp(&a),
+        }
+    }
+
+    pub fn is_better(self, score: u32, than: u32) -> bool {
+        self.compare(score, than) == Ordering::Less
+    }
+}
+
+// How a level's runs are scored, the "scoring" object of a level in levels.json. Any
+// field left out keeps the classic scoring: time plus a second per wrong key, less the
+// streak bonus, lower is better.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Scoring {
+    // Multiplies the milliseconds taken
+    pub time_weight: f32,
+    pub wrong_key_penalty_ms: u32,
+    // Taken off for each streak letter, times the multiplier at the time
+    pub streak_bonus_ms: u32,
+    // Seconds a good run takes. Defaults to half the letter time for every prompt.
+    pub par_time: Option<f32>,
+    // With "higher" the score is points for beating twice the par time
+    pub better: ScoreOrder,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            time_weight: 1.0,
+            wrong_key_penalty_ms: WRONG_KEY_PENALTY_MS,
+            streak_bonus_ms: STREAK_BONUS_MS,
+            par_time: None,
+            better: ScoreOrder::Lower,
+        }
+    }
+}
+
+impl Scoring {
+    pub fn weighted_time(&self, time_ms: u32) -> u32 {
+        (time_ms as f32 * self.time_weight).round() as u32
+    }
+
+    pub fn penalty(&self, fail_count: u32) -> u32 {
+        fail_count * self.wrong_key_penalty_ms
+    }
+
+    // Weighted time and penalties less the bonus, lower is better whatever the order.
+    // Changing it changes what every later score means, so the leaderboard starts its
+    // all time boards over when it does, see season::ALL_TIME in the lambda.
+    pub fn cost(&self, time_ms: u32, fail_count: u32, streak_bonus_ms: u32) -> u32 {
+        (self.weighted_time(time_ms) + self.penalty(fail_count)).saturating_sub(streak_bonus_ms)
+    }
+
+    pub fn score(&self, cost: u32, par_ms: u32) -> u32 {
+        match self.better {
+            ScoreOrder::Lower => cost,
+            ScoreOrder::Higher => (par_ms * 2).saturating_sub(cost),
+        }
+    }
+
+    pub fn compare(&self, a: u32, b: u32) -> Ordering {
+        self.better.compare(a, b)
+    }
+
+    pub fn is_better(&self, score: u32, than: u32) -> bool {
+        self.better.is_better(score, than)
+    }
+
+    // Three stars at par, two within half as long again, one for finishing
+    pub fn stars(&self, cost: u32, par_ms: u32) -> u8 {
+        if cost <= par_ms {
+            3
+        } else if cost <= par_ms + par_ms / 2 {
+            2
+        } else {
+            1
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+// A kind of weed a level can grow, one of the "weeds" of a level in levels.json. Any
+// field left out is the same as a plain weed. A level without weeds only grows plain
+// ones.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WeedType {
+    pub name: String,
+    // Correct letters it takes to clear
+    pub hits: u32,
+    // Wrong keys counted for a miss on its prompt
+    pub miss_cost: u32,
+    // Its prompt running out grows another weed instead of losing the level
+    pub spreads: bool,
+}
+
+// Spreads a level allows. After that a spreader running out of time loses the level
+// like any other weed, so an idle player can't keep it going forever.
+pub const MAX_SPREADS: u32 = 10;
+
+impl Default for WeedType {
+    fn default() -> Self {
+        Self {
+            name: "plain".into(),
+            hits: 1,
+            miss_cost: 1,
+            spreads: false,
+        }
+    }
+}
+
+impl WeedType {
+    pub fn is_plain(&self) -> bool {
+        self.hits <= 1 && self.miss_cost <= 1 && !self.spreads
+    }
+}
@@ -5,7 +5,9 @@ set -o pipefail
 
 source .private/env
 
-docker buildx build --platform linux/amd64 --load -t wack-a-weed-leaderboard:dev .
+# Built from the repo root, the lambda shares the game's rules crate and replays
+# submitted runs against the level definitions the game ships with
+docker buildx build --platform linux/amd64 --load -t wack-a-weed-leaderboard:dev -f Dockerfile ../..
 
  docker run \
     -it \
@@ -13,7 +15,13 @@ docker buildx build --platform linux/amd64 --load -t wack-a-weed-leaderboard:dev
     -v ~/.aws:/root/.aws \
     -e AWS_PROFILE=$AWS_PROFILE \
     wack-a-weed-leaderboard:dev \
-    cargo lambda deploy --iam-role arn:aws:iam::"$AWS_ACCOUNT_ID":role/$IAM_ROLE wack-a-weed-leaderboard-lambda
+    cargo lambda deploy --iam-role arn:aws:iam::"$AWS_ACCOUNT_ID":role/$IAM_ROLE \
+        --include levels.json \
+        --env-var LEADERBOARD_SIGNING_KEY="$LEADERBOARD_SIGNING_KEY" \
+        --env-var LEADERBOARD_ADMIN_TOKEN="$LEADERBOARD_ADMIN_TOKEN" \
+        --env-var LEADERBOARD_TABLE=wack_a_weed_leaderboard_boards \
+        --env-var LEADERBOARD_ADMIN_TABLE=wack_a_weed_leaderboard_admin \
+        wack-a-weed-leaderboard-lambda
     ## Instead of
     
     # TODO 
@@ -0,0 +1,568 @@
+use crate::run::{RunError, RunSummary, RunVerifier};
+use crate::season;
+use crate::storage::{
+    Board, Error, ReplayRecord, ScoreEntry, Storage, Submission, SubmissionRecord,
+};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+use weed_core::{NameRules, group_code};
+
+mod admin;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 100;
+
+// Input modes other than typing, each ranked on its own boards
+const MODES: [&str; 1] = ["click"];
+
+// Routes:
+//   GET  /scores/{level}?limit=10&name=...  top scores, plus the named player's rank
+//   POST /scores?limit=10                   submit a signed run, returns the updated board
+//   GET  /players/{name}                    a player's best score and rank on each level
+//   GET  /replays/{level}?name=...          the inputs behind an all time best, to race as
+//                                           a ghost. Defaults to the level's leader.
+//
+// Scores and players also take ?season=all|month|week, or the id of an archived season such as
+// 2025-03 or 2025-W07. It defaults to all. ?group=CODE switches to a group's boards
+// instead of the global ones. Scores also take ?mode=click for runs played by clicking,
+// posted runs say their own mode.
+//
+// Moderation routes under /admin are listed in api/admin.rs.
+
+#[derive(Serialize, Debug)]
+pub struct PlayerRank {
+    pub name: String,
+    pub score: u32,
+    pub rank: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Output {
+    pub season: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    pub leaderboard: Vec<ScoreEntry>,
+    pub rank: Option<PlayerRank>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission: Option<Submission>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlayerLevel {
+    pub level: usize,
+    pub score: u32,
+    pub rank: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlayerStats {
+    pub name: String,
+    pub season: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub levels_played: usize,
+    pub total_score: u32,
+    pub levels: Vec<PlayerLevel>,
+}
+
+#[derive(Serialize, Debug)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+// Everything requests are handled with
+pub struct Leaderboard<S> {
+    pub storage: S,
+    pub verifier: RunVerifier,
+    pub names: NameRules,
+    // Admin routes are turned off without one
+    pub admin_token: Option<String>,
+}
+
+impl<S: Storage> Leaderboard<S> {
+    // See `RunVerifier::from_env`
+    pub fn from_env(storage: S) -> Result<Self, Error> {
+        Self::with_verifier(storage, RunVerifier::from_env()?)
+    }
+
+    // LEADERBOARD_BLOCKLIST points at a file of extra blocked name words, one per line,
+    // and LEADERBOARD_ADMIN_TOKEN is the bearer token the admin routes expect
+    pub fn with_verifier(storage: S, verifier: RunVerifier) -> Result<Self, Error> {
+        let mut names = NameRules::default();
+        if let Ok(path) = env::var("LEADERBOARD_BLOCKLIST") {
+            names = names.with_blocklist(&std::fs::read_to_string(path)?);
+        }
+        Ok(Self {
+            storage,
+            verifier,
+            names,
+            admin_token: env::var("LEADERBOARD_ADMIN_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        })
+    }
+
+    // A level's board, ranked the way the level scores
+    fn board(&self, season: impl Into<String>, level: usize) -> Board {
+        Board::new(season, level).with_order(self.verifier.score_order(level))
+    }
+}
+
+// Framework independent request and response, so the lambda and the local server can
+// share one handler
+pub struct ApiRequest<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    // The Authorization header
+    pub authorization: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiResponse {
+    fn json(status: u16, data: &impl Serialize) -> Self {
+        match serde_json::to_string(data) {
+            Ok(body) => Self { status, body },
+            Err(e) => ApiError::internal(e.to_string()).into_response(),
+        }
+    }
+
+    fn empty(status: u16) -> Self {
+        Self {
+            status,
+            body: String::new(),
+        }
+    }
+
+    // The wasm build is served from a different origin than the API
+    pub fn headers(&self) -> [(&'static str, &'static str); 4] {
+        [
+            ("Content-Type", "application/json"),
+            ("Access-Control-Allow-Origin", "*"),
+            (
+                "Access-Control-Allow-Methods",
+                "GET, POST, PUT, DELETE, OPTIONS",
+            ),
+            (
+                "Access-Control-Allow-Headers",
+                "Content-Type, Authorization",
+            ),
+        ]
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: 401,
+            message: "Unauthorized".into(),
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: 403,
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            status: 404,
+            message: "Not found".into(),
+        }
+    }
+
+    fn method_not_allowed() -> Self {
+        Self {
+            status: 405,
+            message: "Method not allowed".into(),
+        }
+    }
+
+    fn unprocessable(message: impl Into<String>) -> Self {
+        Self {
+            status: 422,
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: 500,
+            message: message.into(),
+        }
+    }
+
+    fn into_response(self) -> ApiResponse {
+        let body = serde_json::to_string(&ErrorBody {
+            error: &self.message,
+        })
+        .unwrap_or_default();
+        ApiResponse {
+            status: self.status,
+            body,
+        }
+    }
+}
+
+impl From<RunError> for ApiError {
+    fn from(e: RunError) -> Self {
+        match e {
+            RunError::BadSignature => Self::forbidden("Invalid signature"),
+            RunError::Implausible(reason) => {
+                Self::unprocessable(format!("Run rejected: {}", reason))
+            }
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+fn decode(s: &str) -> String {
+    percent_decode_str(&s.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+fn query_param(query: Option<&str>, key: &str) -> Option<String> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| decode(v))
+}
+
+fn season(query: Option<&str>) -> Result<String, ApiError> {
+    let season = query_param(query, "season");
+    season::resolve(season.as_deref(), SystemTime::now())
+        .ok_or_else(|| ApiError::bad_request("season must be all, month, week or a season id"))
+}
+
+fn valid_group(code: Option<&str>) -> Result<Option<String>, ApiError> {
+    match code.filter(|code| !code.trim().is_empty()) {
+        Some(code) => group_code(code)
+            .map(Some)
+            .map_err(|e| ApiError::bad_request(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+fn group(query: Option<&str>) -> Result<Option<String>, ApiError> {
+    valid_group(query_param(query, "group").as_deref())
+}
+
+fn valid_mode(mode: Option<&str>) -> Result<Option<String>, ApiError> {
+    match mode.filter(|mode| !mode.is_empty()) {
+        Some(mode) if MODES.contains(&mode) => Ok(Some(mode.to_string())),
+        Some(_) => Err(ApiError::bad_request(format!(
+            "mode must be one of {}",
+            MODES.join(", ")
+        ))),
+        None => Ok(None),
+    }
+}
+
+fn mode(query: Option<&str>) -> Result<Option<String>, ApiError> {
+    valid_mode(query_param(query, "mode").as_deref())
+}
+
+fn limit(query: Option<&str>) -> Result<usize, ApiError> {
+    match query_param(query, "limit") {
+        Some(limit) => limit
+            .parse::<usize>()
+            .map(|limit| limit.clamp(1, MAX_LIMIT))
+            .map_err(|_| ApiError::bad_request("limit must be a number")),
+        None => Ok(DEFAULT_LIMIT),
+    }
+}
+
+pub async fn handle<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    request: ApiRequest<'_>,
+) -> ApiResponse {
+    if request.method == "OPTIONS" {
+        return ApiResponse::empty(204);
+    }
+
+    let segments = request
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode)
+        .collect::<Vec<_>>();
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let result = match (request.method, segments.as_slice()) {
+        ("GET", ["scores", level]) => get_scores(leaderboard, level, request.query).await,
+        ("POST", ["scores"]) => post_score(leaderboard, request.body, request.query).await,
+        ("GET", ["players", name]) => get_player(leaderboard, name, request.query).await,
+        ("GET", ["replays", level]) => get_replay(leaderboard, level, request.query).await,
+        (_, ["admin", route @ ..]) => admin::handle(leaderboard, &request, route).await,
+        (_, ["scores", _] | ["scores"] | ["players", _] | ["replays", _]) => {
+            Err(ApiError::method_not_allowed())
+        }
+        _ => Err(ApiError::not_found()),
+    };
+
+    result.unwrap_or_else(ApiError::into_response)
+}
+
+async fn player_rank<S: Storage>(
+    storage: &S,
+    name: &str,
+    board: &Board,
+) -> Result<Option<PlayerRank>, ApiError> {
+    let Some(score) = storage.best_score(name, board).await? else {
+        return Ok(None);
+    };
+    Ok(Some(PlayerRank {
+        rank: storage.scores_better(board, score).await? + 1,
+        name: name.to_string(),
+        score,
+    }))
+}
+
+async fn get_scores<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    level: &str,
+    query: Option<&str>,
+) -> Result<ApiResponse, ApiError> {
+    let storage = &leaderboard.storage;
+    let level = level
+        .parse::<usize>()
+        .map_err(|_| ApiError::bad_request("level must be a number"))?;
+    let board = leaderboard
+        .board(season(query)?, level)
+        .with_group(group(query)?)
+        .with_mode(mode(query)?);
+
+    let leaderboard = storage.top_scores(&board, limit(query)?).await?;
+    let rank = match query_param(query, "name") {
+        Some(name) if !name.is_empty() => player_rank(storage, &name, &board).await?,
+        _ => None,
+    };
+
+    Ok(ApiResponse::json(
+        200,
+        &Output {
+            season: board.season,
+            group: board.group,
+            mode: board.mode,
+            leaderboard,
+            rank,
+            submission: None,
+        },
+    ))
+}
+
+async fn post_score<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    body: &[u8],
+    query: Option<&str>,
+) -> Result<ApiResponse, ApiError> {
+    let storage = &leaderboard.storage;
+    let seasons = season::current(SystemTime::now());
+    let board = Board::new(season(query)?, 0).with_group(group(query)?);
+    if !seasons.contains(&board.season) {
+        return Err(ApiError::bad_request(
+            "Scores can only be submitted to a current season",
+        ));
+    }
+
+    let input: RunSummary = serde_json::from_slice(body)
+        .map_err(|e| ApiError::bad_request(format!("Invalid input: {}", e)))?;
+
+    // The game only submits names that pass the same rules
+    let name = leaderboard
+        .names
+        .validate(&input.name)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let run_group = valid_group(input.group.as_deref())?;
+    let run_mode = valid_mode(input.mode.as_deref())?;
+    if board.group.is_some() && board.group != run_group {
+        return Err(ApiError::bad_request(
+            "Only the run's own group board can be returned",
+        ));
+    }
+    if storage.is_banned(&name).await? {
+        return Err(ApiError::forbidden("This name is banned"));
+    }
+
+    let score = leaderboard.verifier.verify(&input)?;
+    storage
+        .record_submission(&SubmissionRecord {
+            name: name.clone(),
+            level: input.level,
+            score,
+            seed: input.seed,
+            submitted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        })
+        .await?;
+    let board = leaderboard
+        .board(board.season, input.level)
+        .with_group(board.group)
+        .with_mode(run_mode.clone());
+
+    // A run counts towards the all time board and the current month and week, both
+    // globally and for its group, within its own mode
+    let mut boards = Vec::new();
+    for season in &seasons {
+        boards.push(
+            leaderboard
+                .board(season.as_str(), input.level)
+                .with_mode(run_mode.clone()),
+        );
+        if run_group.is_some() {
+            boards.push(
+                leaderboard
+                    .board(season.as_str(), input.level)
+                    .with_group(run_group.clone())
+                    .with_mode(run_mode.clone()),
+            );
+        }
+    }
+    let all_time = leaderboard.board(season::ALL_TIME, input.level);
+    let mut submission = None;
+    for other in boards {
+        let result = storage
+            .submit_score(&ScoreEntry::add(&name, score, other.clone()))
+            .await?;
+        // The all time best is the run others race as a ghost. Ghosts are typed, so
+        // other modes only keep their scores.
+        if other == all_time && result.personal_best {
+            storage
+                .save_replay(&ReplayRecord {
+                    name: name.clone(),
+                    level: input.level,
+                    seed: input.seed,
+                    inputs: input.inputs.clone(),
+                    score,
+                })
+                .await?;
+        }
+        if other == board {
+            submission = Some(result);
+        }
+    }
+    let submission =
+        submission.ok_or_else(|| ApiError::internal("The run's board was not submitted"))?;
+    let leaders = storage.top_scores(&board, limit(query)?).await?;
+    let rank = player_rank(storage, &name, &board).await?;
+
+    let status = if submission.personal_best { 201 } else { 200 };
+    Ok(ApiResponse::json(
+        status,
+        &Output {
+            season: board.season,
+            group: board.group,
+            mode: board.mode,
+            leaderboard: leaders,
+            rank,
+            submission: Some(submission),
+        },
+    ))
+}
+
+async fn get_replay<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    level: &str,
+    query: Option<&str>,
+) -> Result<ApiResponse, ApiError> {
+    let storage = &leaderboard.storage;
+    let level = level
+        .parse::<usize>()
+        .map_err(|_| ApiError::bad_request("level must be a number"))?;
+    let name = match query_param(query, "name") {
+        Some(name) if !name.is_empty() => Some(name),
+        _ => storage
+            .top_scores(&leaderboard.board(season::ALL_TIME, level), 1)
+            .await?
+            .into_iter()
+            .next()
+            .map(|entry| entry.name),
+    };
+    let replay = match name {
+        Some(name) => storage.replay(&name, level).await?,
+        None => None,
+    };
+    match replay {
+        Some(replay) => Ok(ApiResponse::json(200, &replay)),
+        None => Err(ApiError {
+            status: 404,
+            message: format!("No replay for level {}", level),
+        }),
+    }
+}
+
+async fn get_player<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    name: &str,
+    query: Option<&str>,
+) -> Result<ApiResponse, ApiError> {
+    let storage = &leaderboard.storage;
+    let season = season(query)?;
+    let group = group(query)?;
+    let mut scores = storage
+        .player_scores(name, &season, group.as_deref())
+        .await?;
+    if scores.is_empty() {
+        return Err(ApiError {
+            status: 404,
+            message: format!("No scores for {}", name),
+        });
+    }
+    scores.sort_by_key(|entry| entry.board.level);
+
+    let mut levels = Vec::with_capacity(scores.len());
+    for entry in scores {
+        let order = leaderboard.verifier.score_order(entry.board.level);
+        let board = entry.board.with_order(order);
+        levels.push(PlayerLevel {
+            rank: storage.scores_better(&board, entry.score).await? + 1,
+            level: board.level,
+            score: entry.score,
+        });
+    }
+
+    Ok(ApiResponse::json(
+        200,
+        &PlayerStats {
+            name: name.to_string(),
+            season,
+            group,
+            levels_played: levels.len(),
+            total_score: levels.iter().map(|level| level.score).sum(),
+            levels,
+        },
+    ))
+}
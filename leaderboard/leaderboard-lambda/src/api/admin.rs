@@ -0,0 +1,234 @@
+use super::{ApiError, ApiRequest, ApiResponse, Leaderboard, limit};
+use crate::storage::{Storage, SubmissionRecord};
+use serde::Serialize;
+use subtle::ConstantTimeEq;
+
+// Routes, all needing `Authorization: Bearer <LEADERBOARD_ADMIN_TOKEN>`:
+//   GET    /admin/submissions?limit=10   most recent accepted runs
+//   DELETE /admin/scores/{level}/{name}  remove a player's entry on a level, every season
+//   GET    /admin/bans                   banned names
+//   PUT    /admin/bans/{name}            ban a name and remove all of its scores
+//   DELETE /admin/bans/{name}            lift a ban
+//
+// Bans hold for any case of the name, but scores are only removed for the name exactly
+// as given.
+
+#[derive(Serialize, Debug)]
+pub struct Submissions {
+    pub submissions: Vec<SubmissionRecord>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Deleted {
+    pub name: String,
+    pub deleted: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Bans {
+    pub bans: Vec<String>,
+}
+
+fn authorize<S>(leaderboard: &Leaderboard<S>, request: &ApiRequest) -> Result<(), ApiError> {
+    let Some(token) = &leaderboard.admin_token else {
+        return Err(ApiError::forbidden("Admin routes are disabled"));
+    };
+    match request
+        .authorization
+        .and_then(|header| header.strip_prefix("Bearer "))
+    {
+        // Compared in constant time so the token can't be guessed a byte at a time
+        Some(given) if given.as_bytes().ct_eq(token.as_bytes()).into() => Ok(()),
+        _ => Err(ApiError::unauthorized()),
+    }
+}
+
+pub(super) async fn handle<S: Storage>(
+    leaderboard: &Leaderboard<S>,
+    request: &ApiRequest<'_>,
+    route: &[&str],
+) -> Result<ApiResponse, ApiError> {
+    authorize(leaderboard, request)?;
+    let storage = &leaderboard.storage;
+
+    match (request.method, route) {
+        ("GET", ["submissions"]) => {
+            let submissions = storage.recent_submissions(limit(request.query)?).await?;
+            Ok(ApiResponse::json(200, &Submissions { submissions }))
+        }
+        ("DELETE", ["scores", level, name]) => {
+            let level = level
+                .parse::<usize>()
+                .map_err(|_| ApiError::bad_request("level must be a number"))?;
+            let deleted = storage.delete_scores(name, Some(level)).await?;
+            if deleted == 0 {
+                return Err(ApiError {
+                    status: 404,
+                    message: format!("No scores for {} on level {}", name, level),
+                });
+            }
+            Ok(ApiResponse::json(
+                200,
+                &Deleted {
+                    name: name.to_string(),
+                    deleted,
+                },
+            ))
+        }
+        ("GET", ["bans"]) => {
+            let bans = storage.banned_names().await?;
+            Ok(ApiResponse::json(200, &Bans { bans }))
+        }
+        ("PUT", ["bans", name]) => {
+            storage.ban_name(name).await?;
+            let deleted = storage.delete_scores(name, None).await?;
+            Ok(ApiResponse::json(
+                200,
+                &Deleted {
+                    name: name.to_string(),
+                    deleted,
+                },
+            ))
+        }
+        ("DELETE", ["bans", name]) => {
+            if !storage.unban_name(name).await? {
+                return Err(ApiError {
+                    status: 404,
+                    message: format!("{} is not banned", name),
+                });
+            }
+            Ok(ApiResponse::empty(204))
+        }
+        (_, ["submissions"] | ["scores", _, _] | ["bans"] | ["bans", _]) => {
+            Err(ApiError::method_not_allowed())
+        }
+        _ => Err(ApiError::not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{ApiRequest, ApiResponse, Leaderboard, handle};
+    use crate::run::RunVerifier;
+    use crate::run::tests::{rules, signed_run};
+    use crate::storage::memory::MemoryStorage;
+    use weed_core::NameRules;
+
+    const TOKEN: &str = "admin-token";
+
+    fn leaderboard() -> Leaderboard<MemoryStorage> {
+        Leaderboard {
+            storage: MemoryStorage::new(),
+            verifier: RunVerifier::new(vec![rules()], "test-key"),
+            names: NameRules::default(),
+            admin_token: Some(TOKEN.into()),
+        }
+    }
+
+    async fn send(
+        leaderboard: &Leaderboard<MemoryStorage>,
+        method: &str,
+        path: &str,
+        authorization: Option<&str>,
+        body: &[u8],
+    ) -> ApiResponse {
+        let request = ApiRequest {
+            method,
+            path,
+            query: None,
+            authorization,
+            body,
+        };
+        handle(leaderboard, request).await
+    }
+
+    async fn admin(
+        leaderboard: &Leaderboard<MemoryStorage>,
+        method: &str,
+        path: &str,
+    ) -> ApiResponse {
+        let bearer = format!("Bearer {}", TOKEN);
+        send(leaderboard, method, path, Some(&bearer), &[]).await
+    }
+
+    async fn post_run(leaderboard: &Leaderboard<MemoryStorage>) -> ApiResponse {
+        let run = signed_run(&leaderboard.verifier);
+        let body = serde_json::json!({
+            "level": run.level,
+            "name": run.name,
+            "seed": run.seed,
+            "keys": run.inputs.keys,
+            "times": run.inputs.times,
+            "score": run.score,
+            "signature": run.signature,
+        });
+        send(
+            leaderboard,
+            "POST",
+            "/scores",
+            None,
+            body.to_string().as_bytes(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn needs_the_token() {
+        let leaderboard = leaderboard();
+        for authorization in [None, Some("Bearer wrong"), Some(TOKEN), Some("Bearer ")] {
+            let response = send(&leaderboard, "GET", "/admin/bans", authorization, &[]).await;
+            assert_eq!(response.status, 401);
+        }
+        assert_eq!(admin(&leaderboard, "GET", "/admin/bans").await.status, 200);
+    }
+
+    #[tokio::test]
+    async fn disabled_without_a_token() {
+        let leaderboard = Leaderboard {
+            admin_token: None,
+            ..leaderboard()
+        };
+        assert_eq!(admin(&leaderboard, "GET", "/admin/bans").await.status, 403);
+    }
+
+    #[tokio::test]
+    async fn deletes_scores() {
+        let leaderboard = leaderboard();
+        assert_eq!(post_run(&leaderboard).await.status, 201);
+
+        assert_eq!(
+            admin(&leaderboard, "DELETE", "/admin/scores/0/ada")
+                .await
+                .status,
+            200
+        );
+        let scores = send(&leaderboard, "GET", "/scores/0", None, &[]).await;
+        assert!(scores.body.contains(r#""leaderboard":[]"#));
+        assert_eq!(
+            admin(&leaderboard, "DELETE", "/admin/scores/0/ada")
+                .await
+                .status,
+            404
+        );
+    }
+
+    #[tokio::test]
+    async fn ban_blocks_later_runs() {
+        let leaderboard = leaderboard();
+        assert_eq!(post_run(&leaderboard).await.status, 201);
+
+        assert_eq!(
+            admin(&leaderboard, "PUT", "/admin/bans/ada").await.status,
+            200
+        );
+        assert_eq!(post_run(&leaderboard).await.status, 403);
+
+        assert_eq!(
+            admin(&leaderboard, "DELETE", "/admin/bans/ADA")
+                .await
+                .status,
+            204
+        );
+        assert_eq!(post_run(&leaderboard).await.status, 201);
+    }
+}
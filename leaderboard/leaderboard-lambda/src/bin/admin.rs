@@ -0,0 +1,138 @@
+// Moderation from the command line, through the lambda's admin routes.
+//
+//   LEADERBOARD_ADMIN_TOKEN=... cargo run --features admin --bin leaderboard-admin -- bans
+//
+// LEADERBOARD_URL defaults to the local server, so this works against
+// `cargo run --features local --bin local-server` started with the same token.
+use leaderboard::storage::{Error, SubmissionRecord};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::Method;
+use reqwest::blocking::{Client, Response};
+use serde::Deserialize;
+use std::env;
+use std::process::ExitCode;
+
+const DEFAULT_URL: &str = "http://127.0.0.1:3000";
+
+const USAGE: &str = "Usage: leaderboard-admin <command>
+
+Commands:
+  submissions [limit]    most recent accepted runs
+  delete <level> <name>  remove a player's entry on a level, every season
+  bans                   list banned names
+  ban <name>             ban a name and remove all of its scores
+  unban <name>           lift a ban";
+
+#[derive(Deserialize)]
+struct Submissions {
+    submissions: Vec<SubmissionRecord>,
+}
+
+#[derive(Deserialize)]
+struct Deleted {
+    name: String,
+    deleted: usize,
+}
+
+#[derive(Deserialize)]
+struct Bans {
+    bans: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+struct Admin {
+    client: Client,
+    url: String,
+    token: String,
+}
+
+impl Admin {
+    fn from_env() -> Result<Self, Error> {
+        let token = env::var("LEADERBOARD_ADMIN_TOKEN")
+            .map_err(|_| "LEADERBOARD_ADMIN_TOKEN is not set")?;
+        let url = env::var("LEADERBOARD_URL").unwrap_or_else(|_| DEFAULT_URL.to_string());
+        Ok(Self {
+            client: Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    fn send(&self, method: Method, path: &str) -> Result<Response, Error> {
+        let response = self
+            .client
+            .request(method, format!("{}/admin{}", self.url, path))
+            .bearer_auth(&self.token)
+            .send()?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        match response.json::<ApiError>() {
+            Ok(body) => Err(format!("{}: {}", status, body.error).into()),
+            Err(_) => Err(status.to_string().into()),
+        }
+    }
+}
+
+fn encode(name: &str) -> String {
+    utf8_percent_encode(name, NON_ALPHANUMERIC).to_string()
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let admin = Admin::from_env()?;
+
+    match args.as_slice() {
+        ["submissions", rest @ ..] if rest.len() <= 1 => {
+            let limit = rest.first().copied().unwrap_or("10");
+            let response = admin.send(Method::GET, &format!("/submissions?limit={}", limit))?;
+            for record in response.json::<Submissions>()?.submissions {
+                println!(
+                    "{}  level {:>3}  score {:>7}  seed {:>20}  {}",
+                    record.submitted_at, record.level, record.score, record.seed, record.name
+                );
+            }
+        }
+        ["delete", level, name] => {
+            let path = format!("/scores/{}/{}", level, encode(name));
+            let deleted = admin.send(Method::DELETE, &path)?.json::<Deleted>()?;
+            println!("Removed {} score(s) for {}", deleted.deleted, deleted.name);
+        }
+        ["bans"] => {
+            for name in admin.send(Method::GET, "/bans")?.json::<Bans>()?.bans {
+                println!("{}", name);
+            }
+        }
+        ["ban", name] => {
+            let path = format!("/bans/{}", encode(name));
+            let deleted = admin.send(Method::PUT, &path)?.json::<Deleted>()?;
+            println!(
+                "Banned {} and removed {} score(s)",
+                deleted.name, deleted.deleted
+            );
+        }
+        ["unban", name] => {
+            admin.send(Method::DELETE, &format!("/bans/{}", encode(name)))?;
+            println!("Unbanned {}", name);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
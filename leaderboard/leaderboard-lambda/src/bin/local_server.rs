@@ -0,0 +1,91 @@
+// A plain HTTP server in front of the same handler as the lambda, so the game can
+// point LEADERBOARD_URL at a local leaderboard during development.
+//
+//   LEADERBOARD_DB=leaderboard.db cargo run --features local --bin local-server
+//
+// Without LEADERBOARD_DB the scores only live in memory. Set LEADERBOARD_ADMIN_TOKEN to
+// try the admin routes with the leaderboard-admin CLI. Submitted runs are checked
+// against LEADERBOARD_LEVELS, e.g. ../../assets/lexi/levels/levels-dev.json for a
+// dev build of the game.
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use leaderboard::api::{self, ApiRequest, Leaderboard};
+use leaderboard::run::RunVerifier;
+use leaderboard::storage::Storage;
+use leaderboard::storage::memory::MemoryStorage;
+use leaderboard::storage::sqlite::SqliteStorage;
+use std::env;
+use std::sync::Arc;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:3000";
+// What the game signs runs with when it's built without LEADERBOARD_SIGNING_KEY. Only
+// the local server falls back to it, the lambda refuses to start without a key.
+const DEV_SIGNING_KEY: &str = "wack-a-weed-local-development";
+
+async fn handler<S: Storage + Send + Sync>(
+    State(leaderboard): State<Arc<Leaderboard<S>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let request = ApiRequest {
+        method: method.as_str(),
+        path: uri.path(),
+        query: uri.query(),
+        authorization: headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok()),
+        body: &body,
+    };
+    let response = api::handle(&leaderboard, request).await;
+
+    (
+        StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        response.headers(),
+        response.body,
+    )
+        .into_response()
+}
+
+async fn serve<S: Storage + Send + Sync + 'static>(
+    leaderboard: Leaderboard<S>,
+    addr: &str,
+) -> std::io::Result<()> {
+    let app = Router::new()
+        .fallback(handler::<S>)
+        .with_state(Arc::new(leaderboard));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Leaderboard listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await
+}
+
+#[tokio::main]
+async fn main() -> Result<(), leaderboard::storage::Error> {
+    let addr = env::var("LEADERBOARD_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let verifier = RunVerifier::from_env_or(DEV_SIGNING_KEY)?;
+
+    match env::var("LEADERBOARD_DB") {
+        Ok(path) => {
+            println!("Storing scores in {}", path);
+            serve(
+                Leaderboard::with_verifier(SqliteStorage::open(path)?, verifier)?,
+                &addr,
+            )
+            .await?;
+        }
+        Err(_) => {
+            println!("Storing scores in memory");
+            serve(
+                Leaderboard::with_verifier(MemoryStorage::new(), verifier)?,
+                &addr,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+// Copies the scores from the leaderboard table as it was before boards into the one
+// LEADERBOARD_TABLE names, see terraform/modules/dynamodb/main.tf.
+//
+//   LEADERBOARD_TABLE=wack_a_weed_leaderboard_boards cargo run --bin leaderboard-migrate -- wack_a_weed_leaderboard
+use leaderboard::storage::dynamodb::DynamoStorage;
+use std::env;
+use std::process::ExitCode;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let Some(old_table_name) = env::args().nth(1) else {
+        eprintln!("Usage: leaderboard-migrate <old table>");
+        return ExitCode::FAILURE;
+    };
+    if env::var("LEADERBOARD_TABLE").is_err() {
+        eprintln!("LEADERBOARD_TABLE is not set");
+        return ExitCode::FAILURE;
+    }
+
+    let storage = DynamoStorage::from_env().await;
+    match storage.import_scores(&old_table_name).await {
+        Ok(copied) => {
+            println!("Copied {} scores from {}", copied, old_table_name);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api;
+pub mod run;
+pub mod season;
+pub mod storage;
@@ -0,0 +1,265 @@
+use crate::storage::Error;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::env;
+use weed_core::{InputLog, LevelRules, ScoreOrder, replay};
+
+// Faster than any human can read a prompt and find the key
+const MIN_PROMPT_MS: u32 = 100;
+
+const DEFAULT_LEVELS_PATH: &str = "levels.json";
+
+type HmacSha256 = Hmac<Sha256>;
+
+// What the game records for a completed level. The run is replayed from the seed and
+// input log, so the submitted score only has to agree with the replay.
+#[derive(Deserialize, Debug)]
+pub struct RunSummary {
+    pub level: usize,
+    pub name: String,
+    pub seed: u64,
+    // Group code the score is also ranked under. Signed, so a run can't be posted again
+    // onto another group's boards.
+    #[serde(default)]
+    pub group: Option<String>,
+    // Input mode the run was played in, e.g. click, when it wasn't typed. Signed, as it
+    // picks the boards the run is ranked on.
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(flatten)]
+    pub inputs: InputLog,
+    pub score: u32,
+    pub signature: String,
+}
+
+impl RunSummary {
+    // Must match `run_summary` in the game's leaderboard.rs
+    pub fn message(&self) -> String {
+        let times = self
+            .inputs
+            .times
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let message = format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.level, self.name, self.seed, self.score, self.inputs.keys, times
+        );
+        // Typed runs outside a group are signed as they always were
+        let message = match &self.mode {
+            Some(mode) => format!("{}|{}", message, mode),
+            None => message,
+        };
+        match &self.group {
+            Some(group) => format!("{}|group:{}", message, group),
+            None => message,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LevelFile {
+    levels: Vec<LevelRules>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RunError {
+    BadSignature,
+    Implausible(String),
+}
+
+pub struct RunVerifier {
+    levels: Vec<LevelRules>,
+    key: Vec<u8>,
+}
+
+impl RunVerifier {
+    pub fn new(levels: Vec<LevelRules>, key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            levels,
+            key: key.into(),
+        }
+    }
+
+    // LEADERBOARD_LEVELS points at the game's levels.json and LEADERBOARD_SIGNING_KEY
+    // must match the key the game was built with. Without a key anyone could sign runs,
+    // so it's an error for it to be missing.
+    pub fn from_env() -> Result<Self, Error> {
+        match env::var("LEADERBOARD_SIGNING_KEY") {
+            Ok(key) if !key.is_empty() => Self::from_env_with_key(key),
+            _ => Err("LEADERBOARD_SIGNING_KEY must be set".into()),
+        }
+    }
+
+    // For local development, where the game falls back to a known key
+    pub fn from_env_or(fallback_key: &str) -> Result<Self, Error> {
+        match env::var("LEADERBOARD_SIGNING_KEY") {
+            Ok(key) if !key.is_empty() => Self::from_env_with_key(key),
+            _ => {
+                eprintln!("LEADERBOARD_SIGNING_KEY is not set, using the development key");
+                Self::from_env_with_key(fallback_key.to_string())
+            }
+        }
+    }
+
+    fn from_env_with_key(key: String) -> Result<Self, Error> {
+        let path = env::var("LEADERBOARD_LEVELS").unwrap_or_else(|_| DEFAULT_LEVELS_PATH.into());
+        let file: LevelFile = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        Ok(Self::new(file.levels, key))
+    }
+
+    // Which way a level's scores rank, lower is better for a level it doesn't know
+    pub fn score_order(&self, level: usize) -> ScoreOrder {
+        self.levels
+            .get(level)
+            .map(|level| level.scoring.better)
+            .unwrap_or_default()
+    }
+
+    pub fn sign(&self, run: &RunSummary) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("hmac accepts any key size");
+        mac.update(run.message().as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    // Returns the recomputed score
+    pub fn verify(&self, run: &RunSummary) -> Result<u32, RunError> {
+        let signature = hex::decode(&run.signature).map_err(|_| RunError::BadSignature)?;
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("hmac accepts any key size");
+        mac.update(run.message().as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| RunError::BadSignature)?;
+
+        let implausible = |reason: String| Err(RunError::Implausible(reason));
+
+        let Some(level) = self.levels.get(run.level) else {
+            return implausible(format!("unknown level {}", run.level));
+        };
+
+        let replay = match replay(level.clone(), run.seed, &run.inputs) {
+            Ok(replay) => replay,
+            Err(e) => return implausible(format!("replay failed, {}", e)),
+        };
+
+        if let Some(time) = replay.prompt_times.iter().find(|&&t| t < MIN_PROMPT_MS) {
+            return implausible(format!("{}ms is faster than humanly possible", time));
+        }
+
+        if replay.score != run.score {
+            return implausible(format!(
+                "score {} does not match the replayed {}",
+                run.score, replay.score
+            ));
+        }
+        Ok(replay.score)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use weed_core::{Run, prompt_keys};
+
+    pub(crate) fn rules() -> LevelRules {
+        LevelRules {
+            letters: ["a", "s", "d", "f"].map(String::from).to_vec(),
+            time: 2.0,
+            count: 5,
+            fail_count: 3,
+            ..Default::default()
+        }
+    }
+
+    fn verifier() -> RunVerifier {
+        RunVerifier::new(vec![rules()], "test-key")
+    }
+
+    // A completed run, signed the way the game signs it
+    pub(crate) fn signed_run(verifier: &RunVerifier) -> RunSummary {
+        let seed = 7;
+        let mut run = Run::new(rules(), seed);
+        let mut inputs = InputLog::default();
+        let mut now = 0;
+        while let Some(prompt) = run.prompt().map(str::to_string) {
+            now += 400;
+            for key in prompt_keys(&prompt) {
+                run.press(now, key);
+                inputs.push(now, key);
+            }
+        }
+        let mut summary = RunSummary {
+            level: 0,
+            name: "ada".into(),
+            seed,
+            group: None,
+            mode: None,
+            inputs,
+            score: run.score(),
+            signature: String::new(),
+        };
+        summary.signature = verifier.sign(&summary);
+        summary
+    }
+
+    #[test]
+    fn accepts_a_signed_run() {
+        let verifier = verifier();
+        let run = signed_run(&verifier);
+        assert_eq!(verifier.verify(&run), Ok(run.score));
+    }
+
+    #[test]
+    fn rejects_a_wrong_score() {
+        let verifier = verifier();
+        let mut run = signed_run(&verifier);
+        run.score -= 1;
+        run.signature = verifier.sign(&run);
+        assert!(matches!(
+            verifier.verify(&run),
+            Err(RunError::Implausible(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_tampered_signature() {
+        let verifier = verifier();
+        let mut run = signed_run(&verifier);
+        run.score -= 1;
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+
+        let mut run = signed_run(&verifier);
+        let flipped = if run.signature.starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        run.signature.replace_range(..1, flipped);
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+
+        let run = signed_run(&RunVerifier::new(vec![rules()], "another-key"));
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+    }
+
+    #[test]
+    fn signs_the_group_and_mode() {
+        let verifier = verifier();
+        let mut run = signed_run(&verifier);
+        run.group = Some("TEAM".into());
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+        run.signature = verifier.sign(&run);
+        assert_eq!(verifier.verify(&run), Ok(run.score));
+
+        run.group = Some("OTHER".into());
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+
+        // A group isn't mistaken for a mode with the same name
+        let mut run = signed_run(&verifier);
+        run.group = Some("CLICK".into());
+        run.signature = verifier.sign(&run);
+        run.group = None;
+        run.mode = Some("CLICK".into());
+        assert_eq!(verifier.verify(&run), Err(RunError::BadSignature));
+    }
+}
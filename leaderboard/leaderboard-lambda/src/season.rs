@@ -0,0 +1,162 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Every score goes on the all time board and on the boards for the current month and
+// week. Past months and weeks stay stored under their ids, so a new season starts an
+// empty board and the old one is archived rather than deleted.
+//
+// The streak bonus took time off every run from then on, so the all time boards started
+// over with it and the ones from before are kept as an archive that can still be read.
+// The month and week it came in mix both until they end.
+pub const ALL_TIME: &str = "all-streaks";
+pub const BEFORE_STREAKS: &str = "all-pre-streaks";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Days since 1970-01-01 to (year, month, day), from Howard Hinnant's date algorithms
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn days_since_epoch(now: SystemTime) -> i64 {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    (seconds / SECONDS_PER_DAY) as i64
+}
+
+// e.g. 2025-03
+pub fn month_id(now: SystemTime) -> String {
+    let (year, month, _) = civil_from_days(days_since_epoch(now));
+    format!("{:04}-{:02}", year, month)
+}
+
+// ISO 8601 week, e.g. 2025-W07. Weeks start on Monday and belong to the year their
+// Thursday falls in.
+pub fn week_id(now: SystemTime) -> String {
+    let days = days_since_epoch(now);
+    // 1970-01-01 was a Thursday
+    let weekday = (days + 3).rem_euclid(7);
+    let thursday = days - weekday + 3;
+    let (year, _, _) = civil_from_days(thursday);
+    let week = (thursday - days_from_civil(year, 1, 1)) / 7 + 1;
+    format!("{:04}-W{:02}", year, week)
+}
+
+// The boards a score submitted now is kept on
+pub fn current(now: SystemTime) -> [String; 3] {
+    [ALL_TIME.to_string(), month_id(now), week_id(now)]
+}
+
+fn is_season_id(id: &str) -> bool {
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    let number = |s: &str, max: u32| digits(s, 2) && (1..=max).contains(&s.parse().unwrap_or(0));
+    if id == ALL_TIME || id == BEFORE_STREAKS {
+        return true;
+    }
+    match id.split_once('-') {
+        Some((year, week)) if week.starts_with('W') => digits(year, 4) && number(&week[1..], 53),
+        Some((year, month)) => digits(year, 4) && number(month, 12),
+        None => false,
+    }
+}
+
+// "all", "month" and "week" mean the current season, anything else has to be the id
+// of one, such as a past week or the all time boards from before streaks
+pub fn resolve(season: Option<&str>, now: SystemTime) -> Option<String> {
+    match season.unwrap_or("all") {
+        "all" => Some(ALL_TIME.to_string()),
+        "month" => Some(month_id(now)),
+        "week" => Some(week_id(now)),
+        id if is_season_id(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Midday, so the day doesn't depend on rounding
+    fn on(year: i64, month: u32, day: u32) -> SystemTime {
+        let days = days_from_civil(year, month, day) as u64;
+        UNIX_EPOCH + Duration::from_secs(days * SECONDS_PER_DAY + SECONDS_PER_DAY / 2)
+    }
+
+    #[test]
+    fn weeks_belong_to_their_thursdays_year() {
+        assert_eq!(week_id(on(2020, 12, 31)), "2020-W53");
+        assert_eq!(week_id(on(2021, 1, 3)), "2020-W53");
+        assert_eq!(week_id(on(2021, 1, 4)), "2021-W01");
+        assert_eq!(week_id(on(2024, 12, 30)), "2025-W01");
+        assert_eq!(week_id(on(2025, 2, 12)), "2025-W07");
+        assert_eq!(week_id(on(1970, 1, 1)), "1970-W01");
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(month_id(on(2025, 3, 1)), "2025-03");
+        assert_eq!(month_id(on(2024, 2, 29)), "2024-02");
+        assert_eq!(month_id(on(2024, 12, 31)), "2024-12");
+        assert_eq!(month_id(on(2025, 1, 1)), "2025-01");
+    }
+
+    #[test]
+    fn resolves_current_and_past_seasons() {
+        let now = on(2025, 2, 12);
+        assert_eq!(resolve(None, now).as_deref(), Some(ALL_TIME));
+        assert_eq!(resolve(Some("all"), now).as_deref(), Some(ALL_TIME));
+        assert_eq!(resolve(Some("month"), now).as_deref(), Some("2025-02"));
+        assert_eq!(resolve(Some("week"), now).as_deref(), Some("2025-W07"));
+        assert_eq!(resolve(Some("2024-11"), now).as_deref(), Some("2024-11"));
+        assert_eq!(resolve(Some("2020-W53"), now).as_deref(), Some("2020-W53"));
+        assert_eq!(resolve(Some(ALL_TIME), now).as_deref(), Some(ALL_TIME));
+        assert_eq!(
+            resolve(Some(BEFORE_STREAKS), now).as_deref(),
+            Some(BEFORE_STREAKS)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_seasons() {
+        for season in [
+            "",
+            "spring",
+            "2025",
+            "2025-3",
+            "25-03",
+            "2025-13",
+            "2025-00",
+            "2025-W7",
+            "2025-W00",
+            "2025-W54",
+            "2025-Wxx",
+            "2025-03-01",
+            "2025-03#1",
+            "ALL",
+            "all-time",
+        ] {
+            assert_eq!(resolve(Some(season), on(2025, 2, 12)), None, "{}", season);
+        }
+    }
+}
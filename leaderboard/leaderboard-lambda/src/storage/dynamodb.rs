@@ -0,0 +1,458 @@
+use super::{
+    Board, Error, ReplayRecord, ScoreEntry, Storage, Submission, SubmissionRecord, ban_key,
+};
+use crate::season::BEFORE_STREAKS;
+use aws_sdk_dynamodb::Client as DynamoClient;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::{
+    AttributeValue, ReturnValue, ReturnValuesOnConditionCheckFailure, Select,
+};
+use std::collections::HashMap;
+use std::env;
+use weed_core::{InputLog, ScoreOrder};
+
+const BOARD_INDEX: &str = "board-index";
+
+// The admin table holds the submission log, the banned names and the saved replays,
+// keyed on (kind, key)
+const SUBMISSION_KIND: &str = "submission";
+const BAN_KIND: &str = "ban";
+const REPLAY_KIND: &str = "replay";
+
+pub struct DynamoStorage {
+    client: DynamoClient,
+    table_name: String,
+    admin_table_name: String,
+}
+
+impl DynamoStorage {
+    pub async fn from_env() -> Self {
+        let table_name =
+            env::var("LEADERBOARD_TABLE").unwrap_or_else(|_| "default_table".to_string());
+        let admin_table_name =
+            env::var("LEADERBOARD_ADMIN_TABLE").unwrap_or_else(|_| format!("{}_admin", table_name));
+
+        let config = aws_config::load_from_env().await;
+        Self {
+            client: DynamoClient::new(&config),
+            table_name,
+            admin_table_name,
+        }
+    }
+
+    // Copies every run from a table keyed on (name, score), as the leaderboard was
+    // before boards, onto the all time boards from before the streak bonus. Only each
+    // player's best per level stays.
+    pub async fn import_scores(&self, old_table_name: &str) -> Result<usize, Error> {
+        let mut copied = 0;
+        let mut start_key = None;
+
+        loop {
+            let result = self
+                .client
+                .scan()
+                .table_name(old_table_name)
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await?;
+
+            for item in result.items() {
+                let number = |key: &str| item.get(key)?.as_n().ok()?.parse::<u32>().ok();
+                let name = item.get("name").and_then(|name| name.as_s().ok());
+                let (Some(name), Some(level), Some(score)) =
+                    (name, number("level"), number("score"))
+                else {
+                    continue;
+                };
+                let board = Board::new(BEFORE_STREAKS, level as usize);
+                self.submit_score(&ScoreEntry::add(name, score, board))
+                    .await?;
+                copied += 1;
+            }
+
+            match result.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => return Ok(copied),
+            }
+        }
+    }
+}
+
+fn entry_from_item(item: &HashMap<String, AttributeValue>) -> Option<ScoreEntry> {
+    let name = item.get("name")?.as_s().ok()?;
+    let score = item.get("score")?.as_n().ok()?.parse::<u32>().ok()?;
+    let board = Board::from_id(item.get("board")?.as_s().ok()?)?;
+    Some(ScoreEntry::add(name, score, board))
+}
+
+fn submission_from_item(item: &HashMap<String, AttributeValue>) -> Option<SubmissionRecord> {
+    let number = |key: &str| item.get(key)?.as_n().ok()?.parse::<u64>().ok();
+    Some(SubmissionRecord {
+        name: item.get("name")?.as_s().ok()?.clone(),
+        level: number("level")? as usize,
+        score: number("score")? as u32,
+        seed: number("seed")?,
+        submitted_at: number("submitted_at")?,
+    })
+}
+
+fn replay_key(name: &str, level: usize) -> String {
+    format!("{}#{}", level, name)
+}
+
+fn replay_from_item(item: &HashMap<String, AttributeValue>) -> Option<ReplayRecord> {
+    let number = |key: &str| item.get(key)?.as_n().ok()?.parse::<u64>().ok();
+    let times = item.get("times")?.as_s().ok()?;
+    Some(ReplayRecord {
+        name: item.get("name")?.as_s().ok()?.clone(),
+        level: number("level")? as usize,
+        seed: number("seed")?,
+        inputs: InputLog {
+            keys: item.get("keys")?.as_s().ok()?.clone(),
+            times: times
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(|t| t.parse().ok())
+                .collect::<Option<Vec<u32>>>()?,
+        },
+        score: number("score")? as u32,
+    })
+}
+
+// How a score compares to the ones it beats, for condition and key expressions
+fn better_than(order: ScoreOrder) -> &'static str {
+    match order {
+        ScoreOrder::Lower => "<",
+        ScoreOrder::Higher => ">",
+    }
+}
+
+fn score_from_item(item: &HashMap<String, AttributeValue>) -> Option<u32> {
+    item.get("score")?.as_n().ok()?.parse::<u32>().ok()
+}
+
+impl Storage for DynamoStorage {
+    async fn submit_score(&self, entry: &ScoreEntry) -> Result<Submission, Error> {
+        // The table is keyed on (name, board), so the put only lands when it's a new best
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("name", AttributeValue::S(entry.name.clone()))
+            .item("score", AttributeValue::N(entry.score.to_string()))
+            .item("board", AttributeValue::S(entry.board.id()))
+            .item("season", AttributeValue::S(entry.board.season.clone()))
+            .item("level", AttributeValue::N(entry.board.level.to_string()))
+            .condition_expression(format!(
+                "attribute_not_exists(#name) OR :score {} #score",
+                better_than(entry.board.order)
+            ))
+            .expression_attribute_names("#name", "name")
+            .expression_attribute_names("#score", "score")
+            .expression_attribute_values(":score", AttributeValue::N(entry.score.to_string()))
+            .return_values(ReturnValue::AllOld)
+            .return_values_on_condition_check_failure(ReturnValuesOnConditionCheckFailure::AllOld)
+            .send()
+            .await;
+
+        match result {
+            Ok(output) => Ok(Submission {
+                personal_best: true,
+                previous: output.attributes().and_then(score_from_item),
+            }),
+            Err(e) => match e.as_service_error() {
+                Some(PutItemError::ConditionalCheckFailedException(failed)) => Ok(Submission {
+                    personal_best: false,
+                    previous: failed.item().and_then(score_from_item),
+                }),
+                _ => Err(e.into()),
+            },
+        }
+    }
+
+    async fn top_scores(&self, board: &Board, limit: usize) -> Result<Vec<ScoreEntry>, Error> {
+        let result = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(BOARD_INDEX)
+            .key_condition_expression("#board = :board")
+            .expression_attribute_names("#board", "board")
+            .expression_attribute_values(":board", AttributeValue::S(board.id()))
+            .scan_index_forward(board.order == ScoreOrder::Lower)
+            .limit(limit as i32)
+            .send()
+            .await?;
+
+        Ok(result.items().iter().filter_map(entry_from_item).collect())
+    }
+
+    async fn best_score(&self, name: &str, board: &Board) -> Result<Option<u32>, Error> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("name", AttributeValue::S(name.to_string()))
+            .key("board", AttributeValue::S(board.id()))
+            .send()
+            .await?;
+
+        Ok(result.item().and_then(score_from_item))
+    }
+
+    async fn scores_better(&self, board: &Board, score: u32) -> Result<usize, Error> {
+        let mut count = 0;
+        let mut start_key = None;
+
+        loop {
+            let result = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .index_name(BOARD_INDEX)
+                .select(Select::Count)
+                .key_condition_expression(format!(
+                    "#board = :board AND #score {} :score",
+                    better_than(board.order)
+                ))
+                .expression_attribute_names("#board", "board")
+                .expression_attribute_names("#score", "score")
+                .expression_attribute_values(":board", AttributeValue::S(board.id()))
+                .expression_attribute_values(":score", AttributeValue::N(score.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await?;
+
+            count += result.count() as usize;
+
+            match result.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => return Ok(count),
+            }
+        }
+    }
+
+    async fn player_scores(
+        &self,
+        name: &str,
+        season: &str,
+        group: Option<&str>,
+    ) -> Result<Vec<ScoreEntry>, Error> {
+        let mut entries = Vec::new();
+        let mut start_key = None;
+
+        loop {
+            let result = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .key_condition_expression("#name = :name AND begins_with(#board, :season)")
+                .expression_attribute_names("#name", "name")
+                .expression_attribute_names("#board", "board")
+                .expression_attribute_values(":name", AttributeValue::S(name.to_string()))
+                .expression_attribute_values(
+                    ":season",
+                    AttributeValue::S(Board::prefix(season, group)),
+                )
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await?;
+
+            entries.extend(result.items().iter().filter_map(entry_from_item));
+
+            match result.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => return Ok(entries),
+            }
+        }
+    }
+
+    async fn record_submission(&self, record: &SubmissionRecord) -> Result<(), Error> {
+        // Sorts by time, the seed keeps two runs in the same second apart
+        let key = format!("{:020}#{}", record.submitted_at, record.seed);
+        self.client
+            .put_item()
+            .table_name(&self.admin_table_name)
+            .item("kind", AttributeValue::S(SUBMISSION_KIND.to_string()))
+            .item("key", AttributeValue::S(key))
+            .item("name", AttributeValue::S(record.name.clone()))
+            .item("level", AttributeValue::N(record.level.to_string()))
+            .item("score", AttributeValue::N(record.score.to_string()))
+            .item("seed", AttributeValue::N(record.seed.to_string()))
+            .item(
+                "submitted_at",
+                AttributeValue::N(record.submitted_at.to_string()),
+            )
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn recent_submissions(&self, limit: usize) -> Result<Vec<SubmissionRecord>, Error> {
+        let result = self
+            .client
+            .query()
+            .table_name(&self.admin_table_name)
+            .key_condition_expression("#kind = :kind")
+            .expression_attribute_names("#kind", "kind")
+            .expression_attribute_values(":kind", AttributeValue::S(SUBMISSION_KIND.to_string()))
+            .scan_index_forward(false)
+            .limit(limit as i32)
+            .send()
+            .await?;
+
+        Ok(result
+            .items()
+            .iter()
+            .filter_map(submission_from_item)
+            .collect())
+    }
+
+    async fn delete_scores(&self, name: &str, level: Option<usize>) -> Result<usize, Error> {
+        let mut boards = Vec::new();
+        let mut start_key = None;
+
+        loop {
+            let result = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .key_condition_expression("#name = :name")
+                .expression_attribute_names("#name", "name")
+                .expression_attribute_values(":name", AttributeValue::S(name.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await?;
+
+            boards.extend(
+                result
+                    .items()
+                    .iter()
+                    .filter_map(entry_from_item)
+                    .filter(|entry| level.is_none_or(|level| entry.board.level == level))
+                    .map(|entry| entry.board),
+            );
+
+            match result.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+
+        for board in &boards {
+            self.client
+                .delete_item()
+                .table_name(&self.table_name)
+                .key("name", AttributeValue::S(name.to_string()))
+                .key("board", AttributeValue::S(board.id()))
+                .send()
+                .await?;
+        }
+        Ok(boards.len())
+    }
+
+    async fn ban_name(&self, name: &str) -> Result<(), Error> {
+        self.client
+            .put_item()
+            .table_name(&self.admin_table_name)
+            .item("kind", AttributeValue::S(BAN_KIND.to_string()))
+            .item("key", AttributeValue::S(ban_key(name)))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn unban_name(&self, name: &str) -> Result<bool, Error> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.admin_table_name)
+            .key("kind", AttributeValue::S(BAN_KIND.to_string()))
+            .key("key", AttributeValue::S(ban_key(name)))
+            .return_values(ReturnValue::AllOld)
+            .send()
+            .await?;
+        Ok(result.attributes().is_some())
+    }
+
+    async fn save_replay(&self, record: &ReplayRecord) -> Result<(), Error> {
+        let times = record
+            .inputs
+            .times
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.client
+            .put_item()
+            .table_name(&self.admin_table_name)
+            .item("kind", AttributeValue::S(REPLAY_KIND.to_string()))
+            .item(
+                "key",
+                AttributeValue::S(replay_key(&record.name, record.level)),
+            )
+            .item("name", AttributeValue::S(record.name.clone()))
+            .item("level", AttributeValue::N(record.level.to_string()))
+            .item("seed", AttributeValue::N(record.seed.to_string()))
+            .item("keys", AttributeValue::S(record.inputs.keys.clone()))
+            .item("times", AttributeValue::S(times))
+            .item("score", AttributeValue::N(record.score.to_string()))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn replay(&self, name: &str, level: usize) -> Result<Option<ReplayRecord>, Error> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.admin_table_name)
+            .key("kind", AttributeValue::S(REPLAY_KIND.to_string()))
+            .key("key", AttributeValue::S(replay_key(name, level)))
+            .send()
+            .await?;
+        Ok(result.item().and_then(replay_from_item))
+    }
+
+    async fn is_banned(&self, name: &str) -> Result<bool, Error> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.admin_table_name)
+            .key("kind", AttributeValue::S(BAN_KIND.to_string()))
+            .key("key", AttributeValue::S(ban_key(name)))
+            .send()
+            .await?;
+        Ok(result.item().is_some())
+    }
+
+    async fn banned_names(&self) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        let mut start_key = None;
+
+        loop {
+            let result = self
+                .client
+                .query()
+                .table_name(&self.admin_table_name)
+                .key_condition_expression("#kind = :kind")
+                .expression_attribute_names("#kind", "kind")
+                .expression_attribute_values(":kind", AttributeValue::S(BAN_KIND.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await?;
+
+            names.extend(
+                result
+                    .items()
+                    .iter()
+                    .filter_map(|item| item.get("key")?.as_s().ok().cloned()),
+            );
+
+            match result.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => return Ok(names),
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::{
+    Board, Error, ReplayRecord, ScoreEntry, Storage, Submission, SubmissionRecord, ban_key,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct MemoryStorage {
+    best_scores: Mutex<HashMap<(String, Board), u32>>,
+    submissions: Mutex<Vec<SubmissionRecord>>,
+    replays: Mutex<HashMap<(String, usize), ReplayRecord>>,
+    bans: Mutex<BTreeSet<String>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    async fn submit_score(&self, entry: &ScoreEntry) -> Result<Submission, Error> {
+        let mut best_scores = self.best_scores.lock().unwrap();
+        let key = (entry.name.clone(), entry.board.clone());
+        let previous = best_scores.get(&key).copied();
+        let personal_best =
+            previous.is_none_or(|score| entry.board.order.is_better(entry.score, score));
+
+        if personal_best {
+            best_scores.insert(key, entry.score);
+        }
+        Ok(Submission {
+            personal_best,
+            previous,
+        })
+    }
+
+    async fn top_scores(&self, board: &Board, limit: usize) -> Result<Vec<ScoreEntry>, Error> {
+        let mut leaders = self
+            .best_scores
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((_, entry_board), _)| entry_board == board)
+            .map(|((name, board), score)| ScoreEntry::add(name, *score, board.clone()))
+            .collect::<Vec<_>>();
+        leaders.sort_by(|a, b| board.order.compare(a.score, b.score));
+        leaders.truncate(limit);
+        Ok(leaders)
+    }
+
+    async fn best_score(&self, name: &str, board: &Board) -> Result<Option<u32>, Error> {
+        Ok(self
+            .best_scores
+            .lock()
+            .unwrap()
+            .get(&(name.to_string(), board.clone()))
+            .copied())
+    }
+
+    async fn scores_better(&self, board: &Board, score: u32) -> Result<usize, Error> {
+        Ok(self
+            .best_scores
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((_, entry_board), entry_score)| {
+                entry_board == board && board.order.is_better(**entry_score, score)
+            })
+            .count())
+    }
+
+    async fn player_scores(
+        &self,
+        name: &str,
+        season: &str,
+        group: Option<&str>,
+    ) -> Result<Vec<ScoreEntry>, Error> {
+        Ok(self
+            .best_scores
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((entry_name, board), _)| {
+                entry_name == name
+                    && board.season == season
+                    && board.group.as_deref() == group
+                    && board.mode.is_none()
+            })
+            .map(|((name, board), score)| ScoreEntry::add(name, *score, board.clone()))
+            .collect())
+    }
+
+    async fn record_submission(&self, record: &SubmissionRecord) -> Result<(), Error> {
+        self.submissions.lock().unwrap().push(record.clone());
+        Ok(())
+    }
+
+    async fn recent_submissions(&self, limit: usize) -> Result<Vec<SubmissionRecord>, Error> {
+        Ok(self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_scores(&self, name: &str, level: Option<usize>) -> Result<usize, Error> {
+        let mut best_scores = self.best_scores.lock().unwrap();
+        let before = best_scores.len();
+        best_scores.retain(|(entry_name, board), _| {
+            entry_name != name || level.is_some_and(|level| board.level != level)
+        });
+        Ok(before - best_scores.len())
+    }
+
+    async fn save_replay(&self, record: &ReplayRecord) -> Result<(), Error> {
+        self.replays
+            .lock()
+            .unwrap()
+            .insert((record.name.clone(), record.level), record.clone());
+        Ok(())
+    }
+
+    async fn replay(&self, name: &str, level: usize) -> Result<Option<ReplayRecord>, Error> {
+        Ok(self
+            .replays
+            .lock()
+            .unwrap()
+            .get(&(name.to_string(), level))
+            .cloned())
+    }
+
+    async fn ban_name(&self, name: &str) -> Result<(), Error> {
+        self.bans.lock().unwrap().insert(ban_key(name));
+        Ok(())
+    }
+
+    async fn unban_name(&self, name: &str) -> Result<bool, Error> {
+        Ok(self.bans.lock().unwrap().remove(&ban_key(name)))
+    }
+
+    async fn is_banned(&self, name: &str) -> Result<bool, Error> {
+        Ok(self.bans.lock().unwrap().contains(&ban_key(name)))
+    }
+
+    async fn banned_names(&self) -> Result<Vec<String>, Error> {
+        Ok(self.bans.lock().unwrap().iter().cloned().collect())
+    }
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use weed_core::{InputLog, ScoreOrder};
+
+pub mod dynamodb;
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(test)]
+mod tests;
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+// One leaderboard: a level within a season, either global or for one group's code.
+// Runs played in another input mode, such as clicking, are ranked on boards of their own.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Board {
+    pub season: String,
+    pub level: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    // Which way the level's scores rank. It comes from the level, so it isn't part of
+    // the board's id.
+    #[serde(skip)]
+    pub order: ScoreOrder,
+}
+
+impl Board {
+    pub fn new(season: impl Into<String>, level: usize) -> Self {
+        Self {
+            season: season.into(),
+            level,
+            group: None,
+            mode: None,
+            order: ScoreOrder::Lower,
+        }
+    }
+
+    pub fn with_group(mut self, group: Option<String>) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: Option<String>) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_order(mut self, order: ScoreOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    // How the board is keyed in storage, e.g. 2025-W07#3, TEAM:2025-W07#3 or
+    // click/2025-W07#3. Starting with the group and season lets a player's scores be
+    // looked up a season at a time, and keeps other modes out of those lookups.
+    pub fn id(&self) -> String {
+        let mode = match &self.mode {
+            Some(mode) => format!("{}/", mode),
+            None => String::new(),
+        };
+        format!(
+            "{}{}{}",
+            mode,
+            Self::prefix(&self.season, self.group.as_deref()),
+            self.level
+        )
+    }
+
+    pub fn prefix(season: &str, group: Option<&str>) -> String {
+        match group {
+            Some(group) => format!("{}:{}#", group, season),
+            None => format!("{}#", season),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        let (mode, id) = match id.split_once('/') {
+            Some((mode, rest)) => (Some(mode.to_string()), rest),
+            None => (None, id),
+        };
+        let (group, rest) = match id.split_once(':') {
+            Some((group, rest)) => (Some(group.to_string()), rest),
+            None => (None, id),
+        };
+        let (season, level) = rest.rsplit_once('#')?;
+        Some(
+            Self::new(season, level.parse().ok()?)
+                .with_group(group)
+                .with_mode(mode),
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub score: u32,
+    #[serde(flatten)]
+    pub board: Board,
+}
+
+impl ScoreEntry {
+    pub fn add(name: impl Into<String>, score: u32, board: Board) -> Self {
+        Self {
+            board,
+            name: name.into(),
+            score,
+        }
+    }
+}
+
+// An accepted run, logged so moderators can look over what's been coming in
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionRecord {
+    pub name: String,
+    pub level: usize,
+    pub score: u32,
+    pub seed: u64,
+    // Seconds since the unix epoch
+    pub submitted_at: u64,
+}
+
+// The inputs behind a player's all-time best on a level, raced against as a ghost
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReplayRecord {
+    pub name: String,
+    pub level: usize,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub inputs: InputLog,
+    pub score: u32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub personal_best: bool,
+    pub previous: Option<u32>,
+}
+
+// Everything the leaderboard needs from a database. Scores rank in their board's order,
+// lower first unless the level says higher is better, and only each player's best score
+// per board is kept.
+pub trait Storage {
+    // Keeps the entry only if it beats the player's current best on that board
+    fn submit_score(
+        &self,
+        entry: &ScoreEntry,
+    ) -> impl Future<Output = Result<Submission, Error>> + Send;
+
+    // Best scores on a board, in order
+    fn top_scores(
+        &self,
+        board: &Board,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<ScoreEntry>, Error>> + Send;
+
+    // The player's best score on a board, if they have one
+    fn best_score(
+        &self,
+        name: &str,
+        board: &Board,
+    ) -> impl Future<Output = Result<Option<u32>, Error>> + Send;
+
+    // Number of scores on a board that beat `score`
+    fn scores_better(
+        &self,
+        board: &Board,
+        score: u32,
+    ) -> impl Future<Output = Result<usize, Error>> + Send;
+
+    // Every board in the season the player has a best score on, globally or within the
+    // group
+    fn player_scores(
+        &self,
+        name: &str,
+        season: &str,
+        group: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<ScoreEntry>, Error>> + Send;
+
+    fn record_submission(
+        &self,
+        record: &SubmissionRecord,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Newest first
+    fn recent_submissions(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<SubmissionRecord>, Error>> + Send;
+
+    // Removes the player's scores for the level on every board, or for every level when
+    // none is given. Returns how many were removed.
+    fn delete_scores(
+        &self,
+        name: &str,
+        level: Option<usize>,
+    ) -> impl Future<Output = Result<usize, Error>> + Send;
+
+    // Replaces the player's saved replay for the level
+    fn save_replay(&self, record: &ReplayRecord) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn replay(
+        &self,
+        name: &str,
+        level: usize,
+    ) -> impl Future<Output = Result<Option<ReplayRecord>, Error>> + Send;
+
+    // Bans are kept by `ban_key`, so they hold whatever the case of the name
+    fn ban_name(&self, name: &str) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Whether the name was banned
+    fn unban_name(&self, name: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn is_banned(&self, name: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn banned_names(&self) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
+}
+
+pub fn ban_key(name: &str) -> String {
+    name.to_lowercase()
+}
@@ -0,0 +1,316 @@
+use super::{
+    Board, Error, ReplayRecord, ScoreEntry, Storage, Submission, SubmissionRecord, ban_key,
+};
+use rusqlite::{Connection, OptionalExtension, Row};
+use std::path::Path;
+use std::sync::Mutex;
+use weed_core::{InputLog, ScoreOrder};
+
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, Error> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS board_scores (
+                name   TEXT    NOT NULL,
+                board  TEXT    NOT NULL,
+                season TEXT    NOT NULL,
+                level  INTEGER NOT NULL,
+                score  INTEGER NOT NULL,
+                PRIMARY KEY (name, board)
+            );
+            CREATE INDEX IF NOT EXISTS board_scores_board ON board_scores (board, score);
+            CREATE TABLE IF NOT EXISTS submissions (
+                id           INTEGER PRIMARY KEY AUTOINCREMENT,
+                name         TEXT    NOT NULL,
+                level        INTEGER NOT NULL,
+                score        INTEGER NOT NULL,
+                seed         INTEGER NOT NULL,
+                submitted_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS bans (
+                name TEXT PRIMARY KEY
+            );
+            CREATE TABLE IF NOT EXISTS replays (
+                name  TEXT    NOT NULL,
+                level INTEGER NOT NULL,
+                seed  INTEGER NOT NULL,
+                keys  TEXT    NOT NULL,
+                times TEXT    NOT NULL,
+                score INTEGER NOT NULL,
+                PRIMARY KEY (name, level)
+            );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+// Rows are selected as (name, score, board)
+fn entry_from_row(row: &Row) -> rusqlite::Result<ScoreEntry> {
+    let id = row.get::<_, String>(2)?;
+    let board = Board::from_id(&id).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(
+            2,
+            rusqlite::types::Type::Text,
+            format!("bad board id {}", id).into(),
+        )
+    })?;
+    Ok(ScoreEntry::add(
+        row.get::<_, String>(0)?,
+        row.get(1)?,
+        board,
+    ))
+}
+
+impl Storage for SqliteStorage {
+    async fn submit_score(&self, entry: &ScoreEntry) -> Result<Submission, Error> {
+        let conn = self.conn.lock().unwrap();
+        let previous: Option<u32> = conn
+            .query_row(
+                "SELECT score FROM board_scores WHERE name = ?1 AND board = ?2",
+                (&entry.name, entry.board.id()),
+                |row| row.get(0),
+            )
+            .optional()?;
+        let personal_best =
+            previous.is_none_or(|score| entry.board.order.is_better(entry.score, score));
+
+        if personal_best {
+            conn.execute(
+                "INSERT OR REPLACE INTO board_scores (name, board, season, level, score)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                (
+                    &entry.name,
+                    entry.board.id(),
+                    &entry.board.season,
+                    entry.board.level,
+                    entry.score,
+                ),
+            )?;
+        }
+        Ok(Submission {
+            personal_best,
+            previous,
+        })
+    }
+
+    async fn top_scores(&self, board: &Board, limit: usize) -> Result<Vec<ScoreEntry>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(match board.order {
+            ScoreOrder::Lower => {
+                "SELECT name, score, board FROM board_scores
+                 WHERE board = ?1 ORDER BY score ASC LIMIT ?2"
+            }
+            ScoreOrder::Higher => {
+                "SELECT name, score, board FROM board_scores
+                 WHERE board = ?1 ORDER BY score DESC LIMIT ?2"
+            }
+        })?;
+        let leaders = statement
+            .query_map((board.id(), limit), entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(leaders)
+    }
+
+    async fn best_score(&self, name: &str, board: &Board) -> Result<Option<u32>, Error> {
+        let score = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT score FROM board_scores WHERE name = ?1 AND board = ?2",
+                (name, board.id()),
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(score)
+    }
+
+    async fn scores_better(&self, board: &Board, score: u32) -> Result<usize, Error> {
+        let count = self.conn.lock().unwrap().query_row(
+            match board.order {
+                ScoreOrder::Lower => {
+                    "SELECT COUNT(*) FROM board_scores WHERE board = ?1 AND score < ?2"
+                }
+                ScoreOrder::Higher => {
+                    "SELECT COUNT(*) FROM board_scores WHERE board = ?1 AND score > ?2"
+                }
+            },
+            (board.id(), score),
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    async fn player_scores(
+        &self,
+        name: &str,
+        season: &str,
+        group: Option<&str>,
+    ) -> Result<Vec<ScoreEntry>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT name, score, board FROM board_scores
+             WHERE name = ?1 AND substr(board, 1, length(?2)) = ?2",
+        )?;
+        let entries = statement
+            .query_map((name, Board::prefix(season, group)), entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    async fn record_submission(&self, record: &SubmissionRecord) -> Result<(), Error> {
+        // SQLite integers are signed, the seed keeps its bits either way
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO submissions (name, level, score, seed, submitted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            (
+                &record.name,
+                record.level,
+                record.score,
+                record.seed as i64,
+                record.submitted_at as i64,
+            ),
+        )?;
+        Ok(())
+    }
+
+    async fn recent_submissions(&self, limit: usize) -> Result<Vec<SubmissionRecord>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT name, level, score, seed, submitted_at FROM submissions
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let records = statement
+            .query_map([limit], |row| {
+                Ok(SubmissionRecord {
+                    name: row.get(0)?,
+                    level: row.get(1)?,
+                    score: row.get(2)?,
+                    seed: row.get::<_, i64>(3)? as u64,
+                    submitted_at: row.get::<_, i64>(4)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    async fn delete_scores(&self, name: &str, level: Option<usize>) -> Result<usize, Error> {
+        let deleted = self.conn.lock().unwrap().execute(
+            "DELETE FROM board_scores WHERE name = ?1 AND (?2 IS NULL OR level = ?2)",
+            (name, level),
+        )?;
+        Ok(deleted)
+    }
+
+    async fn save_replay(&self, record: &ReplayRecord) -> Result<(), Error> {
+        // Times are kept comma separated, the way runs are signed
+        let times = record
+            .inputs
+            .times
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO replays (name, level, seed, keys, times, score)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (name, level) DO UPDATE SET
+                seed = excluded.seed,
+                keys = excluded.keys,
+                times = excluded.times,
+                score = excluded.score",
+            (
+                &record.name,
+                record.level,
+                record.seed as i64,
+                &record.inputs.keys,
+                times,
+                record.score,
+            ),
+        )?;
+        Ok(())
+    }
+
+    async fn replay(&self, name: &str, level: usize) -> Result<Option<ReplayRecord>, Error> {
+        let row = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT seed, keys, times, score FROM replays WHERE name = ?1 AND level = ?2",
+                (name, level),
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)? as u64,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, u32>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((seed, keys, times, score)) = row else {
+            return Ok(None);
+        };
+        let times = times
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<u32>, _>>()?;
+        Ok(Some(ReplayRecord {
+            name: name.to_string(),
+            level,
+            seed,
+            inputs: InputLog { keys, times },
+            score,
+        }))
+    }
+
+    async fn ban_name(&self, name: &str) -> Result<(), Error> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR IGNORE INTO bans (name) VALUES (?1)",
+            [ban_key(name)],
+        )?;
+        Ok(())
+    }
+
+    async fn unban_name(&self, name: &str) -> Result<bool, Error> {
+        let deleted = self
+            .conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM bans WHERE name = ?1", [ban_key(name)])?;
+        Ok(deleted > 0)
+    }
+
+    async fn is_banned(&self, name: &str) -> Result<bool, Error> {
+        let banned = self.conn.lock().unwrap().query_row(
+            "SELECT EXISTS (SELECT 1 FROM bans WHERE name = ?1)",
+            [ban_key(name)],
+            |row| row.get(0),
+        )?;
+        Ok(banned)
+    }
+
+    async fn banned_names(&self) -> Result<Vec<String>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare("SELECT name FROM bans ORDER BY name")?;
+        let names = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(names)
+    }
+}
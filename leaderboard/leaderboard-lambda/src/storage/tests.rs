@@ -0,0 +1,189 @@
+// The same checks run against every backend, so they all rank and filter alike
+use super::memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+use super::sqlite::SqliteStorage;
+use super::{Board, ScoreEntry, Storage};
+use weed_core::ScoreOrder;
+
+async fn submit(storage: &impl Storage, name: &str, score: u32, board: &Board) -> bool {
+    storage
+        .submit_score(&ScoreEntry::add(name, score, board.clone()))
+        .await
+        .unwrap()
+        .personal_best
+}
+
+async fn keeps_best_score(storage: impl Storage) {
+    let board = Board::new("all", 0);
+
+    assert!(submit(&storage, "ada", 300, &board).await);
+    assert!(submit(&storage, "ada", 200, &board).await);
+    assert!(!submit(&storage, "ada", 250, &board).await);
+    assert_eq!(storage.best_score("ada", &board).await.unwrap(), Some(200));
+
+    // Another board keeps its own best
+    let other = Board::new("all", 1);
+    assert!(submit(&storage, "ada", 400, &other).await);
+    assert_eq!(storage.best_score("ada", &other).await.unwrap(), Some(400));
+    assert_eq!(storage.best_score("bob", &board).await.unwrap(), None);
+}
+
+async fn top_scores_in_order(storage: impl Storage) {
+    let board = Board::new("all", 0);
+    for (name, score) in [("ada", 300), ("bob", 100), ("cy", 200), ("dee", 400)] {
+        submit(&storage, name, score, &board).await;
+    }
+    submit(&storage, "eve", 50, &Board::new("all", 1)).await;
+
+    let top = storage.top_scores(&board, 3).await.unwrap();
+    let top = top
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.score))
+        .collect::<Vec<_>>();
+    assert_eq!(top, [("bob", 100), ("cy", 200), ("ada", 300)]);
+}
+
+async fn scores_better_rank(storage: impl Storage) {
+    let board = Board::new("all", 0);
+    for (name, score) in [("ada", 300), ("bob", 100), ("cy", 200)] {
+        submit(&storage, name, score, &board).await;
+    }
+    submit(&storage, "eve", 50, &Board::new("all", 1)).await;
+
+    assert_eq!(storage.scores_better(&board, 100).await.unwrap(), 0);
+    assert_eq!(storage.scores_better(&board, 250).await.unwrap(), 2);
+    assert_eq!(storage.scores_better(&board, 1000).await.unwrap(), 3);
+}
+
+async fn ranks_higher_first(storage: impl Storage) {
+    let board = Board::new("all", 0).with_order(ScoreOrder::Higher);
+    for (name, score) in [("ada", 300), ("bob", 100), ("cy", 200)] {
+        submit(&storage, name, score, &board).await;
+    }
+    assert!(submit(&storage, "bob", 400, &board).await);
+    assert!(!submit(&storage, "ada", 250, &board).await);
+
+    let top = storage.top_scores(&board, 2).await.unwrap();
+    let top = top
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.score))
+        .collect::<Vec<_>>();
+    assert_eq!(top, [("bob", 400), ("ada", 300)]);
+    assert_eq!(storage.scores_better(&board, 250).await.unwrap(), 2);
+    assert_eq!(storage.scores_better(&board, 400).await.unwrap(), 0);
+}
+
+async fn player_scores_by_prefix(storage: impl Storage) {
+    let global = Board::new("2025-W07", 0);
+    let group = Board::new("2025-W07", 0).with_group(Some("TEAM".into()));
+    for board in [
+        global.clone(),
+        Board::new("2025-W07", 1),
+        group.clone(),
+        Board::new("2025-W08", 0),
+        Board::new("2025-W07", 0).with_mode(Some("click".into())),
+    ] {
+        submit(&storage, "ada", 100, &board).await;
+    }
+    submit(&storage, "bob", 100, &global).await;
+
+    let mut levels = storage
+        .player_scores("ada", "2025-W07", None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| {
+            assert_eq!(entry.board.group, None);
+            assert_eq!(entry.board.mode, None);
+            entry.board.level
+        })
+        .collect::<Vec<_>>();
+    levels.sort();
+    assert_eq!(levels, [0, 1]);
+
+    let grouped = storage
+        .player_scores("ada", "2025-W07", Some("TEAM"))
+        .await
+        .unwrap();
+    assert_eq!(grouped.len(), 1);
+    assert_eq!(grouped[0].board, group);
+}
+
+async fn delete_scores(storage: impl Storage) {
+    for board in [
+        Board::new("all", 0),
+        Board::new("2025-W07", 0),
+        Board::new("all", 1),
+    ] {
+        submit(&storage, "ada", 100, &board).await;
+        submit(&storage, "bob", 100, &board).await;
+    }
+
+    assert_eq!(storage.delete_scores("ada", Some(0)).await.unwrap(), 2);
+    assert_eq!(
+        storage
+            .best_score("ada", &Board::new("all", 0))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        storage
+            .best_score("ada", &Board::new("all", 1))
+            .await
+            .unwrap(),
+        Some(100)
+    );
+
+    assert_eq!(storage.delete_scores("ada", None).await.unwrap(), 1);
+    assert_eq!(storage.delete_scores("ada", None).await.unwrap(), 0);
+    assert_eq!(
+        storage
+            .best_score("bob", &Board::new("all", 0))
+            .await
+            .unwrap(),
+        Some(100)
+    );
+}
+
+macro_rules! storage_tests {
+    ($backend:ident, $storage:expr) => {
+        mod $backend {
+            use super::*;
+
+            #[tokio::test]
+            async fn keeps_best_score() {
+                super::keeps_best_score($storage).await;
+            }
+
+            #[tokio::test]
+            async fn top_scores_in_order() {
+                super::top_scores_in_order($storage).await;
+            }
+
+            #[tokio::test]
+            async fn scores_better_rank() {
+                super::scores_better_rank($storage).await;
+            }
+
+            #[tokio::test]
+            async fn ranks_higher_first() {
+                super::ranks_higher_first($storage).await;
+            }
+
+            #[tokio::test]
+            async fn player_scores_by_prefix() {
+                super::player_scores_by_prefix($storage).await;
+            }
+
+            #[tokio::test]
+            async fn delete_scores() {
+                super::delete_scores($storage).await;
+            }
+        }
+    };
+}
+
+storage_tests!(memory, MemoryStorage::new());
+#[cfg(feature = "sqlite")]
+storage_tests!(sqlite, SqliteStorage::open_in_memory().unwrap());
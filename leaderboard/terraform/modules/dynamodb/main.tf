@@ -2,6 +2,16 @@ provider "aws" {
   region = "us-east-1"
 }
 
+# The table from before boards, every run keyed on (name, score). Changing a table's
+# key schema replaces it and drops every score, so the boards live in a table of their
+# own and this one stays until its scores are copied across:
+#
+#   LEADERBOARD_TABLE=wack_a_weed_leaderboard_boards cargo run --bin leaderboard-migrate -- wack_a_weed_leaderboard
+#
+# Each player's best per level lands on the "all-pre-streaks" boards, the all time
+# boards from before the streak bonus, which can still be read next to the current
+# "all-streaks" ones. Remove this resource once
+# the lambda is deployed against the new table.
 resource "aws_dynamodb_table" "wack_a_weed_leaderboard" {
   name           = "wack_a_weed_leaderboard"
   billing_mode   = "PROVISIONED"
@@ -34,6 +44,79 @@ resource "aws_dynamodb_table" "wack_a_weed_leaderboard" {
     type = "N"
   }
 
+  lifecycle {
+    prevent_destroy = true
+  }
+
+  tags = {
+    Environment = "dev"
+    Project     = "wack-a-weed"
+  }
+}
+
+resource "aws_dynamodb_table" "wack_a_weed_leaderboard_boards" {
+  name           = "wack_a_weed_leaderboard_boards"
+  billing_mode   = "PROVISIONED"
+  read_capacity  = 1
+  write_capacity = 1
+
+  # One best score per player per board. A board is "<season>#<level>", where the
+  # season is "all", a month (2025-03) or an ISO week (2025-W07). Group boards are
+  # prefixed with the group code, "<group>:<season>#<level>".
+  hash_key  = "name"
+  range_key = "board"
+
+  global_secondary_index {
+    name            = "board-index"
+    hash_key        = "board"
+    range_key       = "score"
+    projection_type = "ALL"
+    read_capacity   = 1
+    write_capacity  = 1
+  }
+
+  attribute {
+    name = "name"
+    type = "S"
+  }
+
+  attribute {
+    name = "board"
+    type = "S"
+  }
+
+  attribute {
+    name = "score"
+    type = "N"
+  }
+
+  tags = {
+    Environment = "dev"
+    Project     = "wack-a-weed"
+  }
+}
+
+# Submission log and banned names for the admin routes. Items are keyed on a kind
+# ("submission" or "ban") and a key within it: the time and seed of a run, or a name.
+resource "aws_dynamodb_table" "wack_a_weed_leaderboard_admin" {
+  name           = "wack_a_weed_leaderboard_admin"
+  billing_mode   = "PROVISIONED"
+  read_capacity  = 1
+  write_capacity = 1
+
+  hash_key  = "kind"
+  range_key = "key"
+
+  attribute {
+    name = "kind"
+    type = "S"
+  }
+
+  attribute {
+    name = "key"
+    type = "S"
+  }
+
   tags = {
     Environment = "dev"
     Project     = "wack-a-weed"
@@ -67,10 +150,16 @@ resource "aws_iam_policy" "dynamodb_access" {
         Effect = "Allow",
         Action = [
           "dynamodb:PutItem",
-          "dynamodb:Scan",
+          "dynamodb:GetItem",
+          "dynamodb:DeleteItem",
+          "dynamodb:Query",
           "dynamodb:DescribeTable"
         ],
-        Resource = aws_dynamodb_table.wack_a_weed_leaderboard.arn
+        Resource = [
+          aws_dynamodb_table.wack_a_weed_leaderboard_boards.arn,
+          "${aws_dynamodb_table.wack_a_weed_leaderboard_boards.arn}/index/*",
+          aws_dynamodb_table.wack_a_weed_leaderboard_admin.arn
+        ]
       }
     ]
   })
@@ -22,7 +22,8 @@ resource "aws_iam_policy" "lambda_policy" {
       Effect = "Allow",
       Action = [
         "dynamodb:PutItem",
-        "dynamodb:Scan",
+        "dynamodb:GetItem",
+        "dynamodb:Query",
         "dynamodb:DescribeTable"
       ],
       Resource = aws_dynamodb_table.dino_game_leaderboard.arn
@@ -57,8 +58,17 @@ resource "aws_apigatewayv2_integration" "lambda_integration" {
 }
 
 resource "aws_apigatewayv2_route" "lambda_route" {
+  for_each = toset([
+    "GET /scores/{level}",
+    "POST /scores",
+    "GET /players/{name}",
+    "GET /replays/{level}",
+    "ANY /admin/{proxy+}",
+    "OPTIONS /{proxy+}",
+  ])
+
   api_id    = aws_apigatewayv2_api.leaderboard_api.id
-  route_key = "POST /submit"
+  route_key = each.value
   target    = "integrations/${aws_apigatewayv2_integration.lambda_integration.id}"
 }
 
@@ -0,0 +1,149 @@
+// Relays a networked 1v1 race between two copies of the game.
+//
+//   cargo run --bin relay
+//
+// Players join a room by name, the first two in a room race each other. The relay
+// picks the seed so both get the same prompts, then forwards each player's progress
+// to the other. Set RELAY_ADDR to listen somewhere other than the default, e.g.
+// 0.0.0.0:4000 to race across a LAN.
+use futures_util::{SinkExt, StreamExt};
+use rand::Rng;
+use std::collections::HashMap;
+use std::env;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_tungstenite::tungstenite::Message;
+use weed_core::RaceMessage;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:4000";
+const PLAYERS_PER_ROOM: usize = 2;
+
+struct Player {
+    addr: SocketAddr,
+    name: String,
+    level: usize,
+    tx: UnboundedSender<Message>,
+}
+
+type Rooms = Arc<Mutex<HashMap<String, Vec<Player>>>>;
+
+fn send(tx: &UnboundedSender<Message>, message: &RaceMessage) {
+    if let Ok(text) = serde_json::to_string(message) {
+        let _ = tx.send(Message::text(text));
+    }
+}
+
+// Adds the player to the room, starting the race when it fills. Returns false when the
+// room already has a race going.
+fn join(rooms: &Rooms, room: &str, player: Player) -> bool {
+    let mut rooms = rooms.lock().unwrap();
+    let players = rooms.entry(room.to_string()).or_default();
+    if players.len() >= PLAYERS_PER_ROOM {
+        send(&player.tx, &RaceMessage::RoomFull);
+        return false;
+    }
+    println!("{} joined room {}", player.name, room);
+    players.push(player);
+
+    if players.len() == PLAYERS_PER_ROOM {
+        let seed = rand::rng().random();
+        let level = players[0].level;
+        for (player, opponent) in players.iter().zip(players.iter().rev()) {
+            send(
+                &player.tx,
+                &RaceMessage::Start {
+                    level,
+                    seed,
+                    opponent: opponent.name.clone(),
+                },
+            );
+        }
+        println!("Race started in room {}", room);
+    }
+    true
+}
+
+fn leave(rooms: &Rooms, room: &str, addr: SocketAddr) {
+    let mut rooms = rooms.lock().unwrap();
+    let Some(players) = rooms.get_mut(room) else {
+        return;
+    };
+    players.retain(|player| player.addr != addr);
+    for player in players.iter() {
+        send(&player.tx, &RaceMessage::OpponentLeft);
+    }
+    if players.is_empty() {
+        rooms.remove(room);
+    }
+}
+
+fn forward(rooms: &Rooms, room: &str, from: SocketAddr, message: Message) {
+    let rooms = rooms.lock().unwrap();
+    for player in rooms.get(room).into_iter().flatten() {
+        if player.addr != from {
+            let _ = player.tx.send(message.clone());
+        }
+    }
+}
+
+async fn connection(rooms: Rooms, stream: TcpStream, addr: SocketAddr) {
+    let Ok(socket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut outgoing, mut incoming) = socket.split();
+    let (tx, mut rx) = unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            if outgoing.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut room = None;
+    while let Some(Ok(message)) = incoming.next().await {
+        let Message::Text(text) = &message else {
+            continue;
+        };
+        let Ok(race_message) = serde_json::from_str::<RaceMessage>(text) else {
+            continue;
+        };
+        match (&room, race_message) {
+            (None, RaceMessage::Join { room: name, name: player, level }) => {
+                let player = Player {
+                    addr,
+                    name: player,
+                    level,
+                    tx: tx.clone(),
+                };
+                if !join(&rooms, &name, player) {
+                    break;
+                }
+                room = Some(name);
+            }
+            (Some(name), RaceMessage::Progress { .. } | RaceMessage::Finished { .. }) => {
+                forward(&rooms, name, addr, message);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(name) = room {
+        leave(&rooms, &name, addr);
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr = env::var("RELAY_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    println!("Relay listening on ws://{}", listener.local_addr()?);
+
+    let rooms = Rooms::default();
+    loop {
+        let (stream, addr) = listener.accept().await?;
+        tokio::spawn(connection(rooms.clone(), stream, addr));
+    }
+}
@@ -8,7 +8,7 @@ pub const RESOLUTION_HEIGHT: f32 = 480.0;
 pub const AFTER_LOADING_STATE: AppState = AppState::Menu;
 pub const RUNNING_SPEED: f32 = 250.0;
 
-use crate::{assets, game, leaderboard, menu, util};
+use crate::{assets, controls, game, leaderboard, menu, util};
 
 pub const DARK_COLOR: Color = Color::srgb(24. / 255., 51. / 255., 5. / 255.);
 pub const LIGHT_COLOR: Color = Color::srgb(214. / 255., 227. / 255., 195. / 255.);
@@ -28,6 +28,11 @@ pub enum AppState {
     HighScores,
     Credits,
     LoadNextLevel,
+    HotSeatSetup,
+    HotSeatResults,
+    SplitScreen,
+    VersusLobby,
+    Controls,
 }
 
 pub fn start() {
@@ -67,6 +72,7 @@ pub fn start() {
             },
             menu::Menu,
             assets::plugin,
+            controls::plugin,
             game::plugin,
             leaderboard::plugin,
             util::plugin,
@@ -4,6 +4,7 @@ use crate::{
 };
 use bevy::prelude::*;
 use bevy_common_assets::json::JsonAssetPlugin;
+use weed_core::{LevelRules, PowerUp, Scoring, WeedType};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(JsonAssetPlugin::<LevelLex>::new(&[".json"]));
@@ -39,4 +40,48 @@ pub struct LevelInfo {
     pub time: f32,
     pub count: u32,
     pub fail_count: u32,
+    #[serde(default)]
+    pub scoring: Scoring,
+    #[serde(default)]
+    pub weeds: Vec<WeedInfo>,
+    #[serde(default)]
+    pub power_ups: Vec<PowerUp>,
+}
+
+// A weed type's gameplay traits and which of the five weed sprites draws it
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct WeedInfo {
+    #[serde(flatten)]
+    pub kind: WeedType,
+    // 1 to 5, any of them when left out
+    #[serde(default)]
+    pub sprite: Option<usize>,
+}
+
+impl LevelInfo {
+    pub fn rules(&self) -> LevelRules {
+        LevelRules {
+            letters: self.letters.clone(),
+            time: self.time,
+            count: self.count,
+            fail_count: self.fail_count,
+            scoring: self.scoring.clone(),
+            weeds: self.weeds.iter().map(|weed| weed.kind.clone()).collect(),
+            power_ups: self.power_ups.clone(),
+        }
+    }
+
+    // The weed sprites this level draws, every one when its weeds don't say
+    pub fn weed_sprites(&self) -> Vec<usize> {
+        let sprites = self
+            .weeds
+            .iter()
+            .map(|weed| weed.sprite)
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+        match sprites.is_empty() {
+            true => (1..=5).collect(),
+            false => sprites,
+        }
+    }
 }
@@ -0,0 +1,669 @@
+use crate::app::*;
+use crate::game::{KeyMap, SceneChange, is_modifier, is_play_button};
+use crate::util::handles::BODY_FONT;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+// What the bindings are saved as between sessions
+const CONTROLS: &str = "controls";
+
+// The keys and gamepad buttons behind starting, menus, pausing and the ghost, and the
+// settings screen to change them. Typing the prompts isn't rebindable.
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Bindings::load())
+        .insert_resource(ControlsCursor::default())
+        .insert_resource(Rebinding::default())
+        .add_systems(OnEnter(AppState::Controls), setup_controls)
+        .add_systems(
+            Update,
+            (
+                capture_binding,
+                controls_navigation,
+                update_controls_display,
+            )
+                .chain()
+                .run_if(in_state(AppState::Controls)),
+        );
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Start,
+    Confirm,
+    Back,
+    Pause,
+    Up,
+    Down,
+    Left,
+    Right,
+    Ghost,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Self::Start,
+        Self::Confirm,
+        Self::Back,
+        Self::Pause,
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Ghost,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Start => "Start",
+            Self::Confirm => "Confirm",
+            Self::Back => "Back",
+            Self::Pause => "Pause",
+            Self::Up => "Navigate up",
+            Self::Down => "Navigate down",
+            Self::Left => "Navigate left",
+            Self::Right => "Navigate right",
+            Self::Ghost => "Change ghost",
+        }
+    }
+
+    // Start, Pause and Ghost are pressed in a level, the rest only on menus
+    fn while_playing(self) -> bool {
+        matches!(self, Self::Start | Self::Pause | Self::Ghost)
+    }
+
+    fn default_binding(self) -> Binding {
+        let (key, button) = match self {
+            Self::Start => (KeyCode::Space, GamepadButton::Start),
+            Self::Confirm => (KeyCode::Enter, GamepadButton::South),
+            Self::Back => (KeyCode::Escape, GamepadButton::East),
+            Self::Pause => (KeyCode::Escape, GamepadButton::Select),
+            Self::Up => (KeyCode::ArrowUp, GamepadButton::DPadUp),
+            Self::Down => (KeyCode::ArrowDown, GamepadButton::DPadDown),
+            Self::Left => (KeyCode::ArrowLeft, GamepadButton::DPadLeft),
+            Self::Right => (KeyCode::ArrowRight, GamepadButton::DPadRight),
+            Self::Ghost => (KeyCode::Tab, GamepadButton::RightThumb),
+        };
+        Binding { key, button }
+    }
+
+    // The left stick always navigates as well as whatever the direction is bound to
+    fn stick(self, stick: Vec2) -> bool {
+        match self {
+            Self::Up => stick.y > STICK_THRESHOLD,
+            Self::Down => stick.y < -STICK_THRESHOLD,
+            Self::Left => stick.x < -STICK_THRESHOLD,
+            Self::Right => stick.x > STICK_THRESHOLD,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Binding {
+    pub key: KeyCode,
+    pub button: GamepadButton,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Bindings(HashMap<Action, Binding>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        )
+    }
+}
+
+impl Bindings {
+    // Saved bindings over the defaults, so an action added since still has its keys
+    fn load() -> Self {
+        let mut bindings = Self::default();
+        let saved = read_saved(CONTROLS).and_then(|json| {
+            serde_json::from_str::<HashMap<Action, Binding>>(&json)
+                .map_err(|e| warn!(?e, "Ignoring saved controls"))
+                .ok()
+        });
+        bindings.0.extend(saved.unwrap_or_default());
+        bindings
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.0) {
+            Ok(json) => write_saved(CONTROLS, &json),
+            Err(e) => error!(?e),
+        }
+    }
+
+    pub fn get(&self, action: Action) -> Binding {
+        self.0
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.get(action).key
+    }
+
+    pub fn button_just_pressed(&self, action: Action, gamepad: &Gamepad) -> bool {
+        gamepad.just_pressed(self.get(action).button)
+    }
+
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        keys.just_pressed(self.key(action))
+            || gamepads
+                .iter()
+                .any(|gamepad| self.button_just_pressed(action, gamepad))
+    }
+
+    // Held, for moving through menus
+    pub fn pressed(
+        &self,
+        action: Action,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        let binding = self.get(action);
+        keys.pressed(binding.key)
+            || gamepads.iter().any(|gamepad| {
+                gamepad.pressed(binding.button) || action.stick(gamepad.left_stick())
+            })
+    }
+
+    // Another action pressed at the same time as this one that's bound the same way.
+    // Back and Pause can share a key, one is for menus and the other for playing.
+    fn bound_elsewhere(&self, action: Action, bound: impl Fn(Binding) -> bool) -> Option<Action> {
+        Action::ALL.into_iter().find(|other| {
+            *other != action
+                && other.while_playing() == action.while_playing()
+                && bound(self.get(*other))
+        })
+    }
+
+    // e.g. "Press Spacebar to Start"
+    pub fn prompt(&self, action: Action, to: &str) -> String {
+        format!("Press {} to {}", key_label(self.key(action)), to)
+    }
+}
+
+// Run condition for an action's key or button being pressed this frame
+pub fn action_just_pressed(
+    action: Action,
+) -> impl FnMut(Res<Bindings>, Res<ButtonInput<KeyCode>>, Query<&Gamepad>) -> bool + Clone {
+    move |bindings: Res<Bindings>, keys: Res<ButtonInput<KeyCode>>, gamepads: Query<&Gamepad>| {
+        bindings.just_pressed(action, &keys, &gamepads)
+    }
+}
+
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Space => "Spacebar".into(),
+        KeyCode::Escape => "Esc".into(),
+        _ => {
+            let name = format!("{:?}", key);
+            ["Key", "Digit", "Arrow"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix))
+                .filter(|rest| !rest.is_empty())
+                .unwrap_or(name.as_str())
+                .to_string()
+        }
+    }
+}
+
+// Face buttons by their Xbox letters, as the gamepad play combos are
+fn button_label(button: GamepadButton) -> String {
+    match button {
+        GamepadButton::South => "A".into(),
+        GamepadButton::East => "B".into(),
+        GamepadButton::West => "X".into(),
+        GamepadButton::North => "Y".into(),
+        _ => format!("{:?}", button),
+    }
+}
+
+// Settings kept between sessions, in a file next to the game or the browser's local
+// storage
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_saved(name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{}.json", name)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_saved(name: &str, json: &str) {
+    if let Err(e) = std::fs::write(format!("{}.json", name), json) {
+        error!(?e, "Couldn't save {}", name);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_saved(name: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("wack-a-weed-{}", name))
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write_saved(name: &str, json: &str) {
+    let key = format!("wack-a-weed-{}", name);
+    if let Some(Err(e)) = local_storage().map(|storage| storage.set_item(&key, json)) {
+        error!(?e, "Couldn't save {}", name);
+    }
+}
+
+// A row for each action, then one to put the defaults back
+#[derive(Resource, Default)]
+struct ControlsCursor(usize);
+
+const RESET_ROW: usize = Action::ALL.len();
+
+// The action waiting for its new key or button, and why the last one was turned down
+#[derive(Resource, Default)]
+struct Rebinding {
+    action: Option<Action>,
+    rejected: Option<String>,
+}
+
+#[derive(Component)]
+struct ControlsRow(usize);
+
+#[derive(Component)]
+struct ControlsKey(Action);
+
+#[derive(Component)]
+struct ControlsButton(Action);
+
+#[derive(Component)]
+struct ControlsFeedback;
+
+fn text(label: impl Into<String>) -> impl Bundle {
+    (
+        TextColor(LIGHT_COLOR),
+        TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+        Pickable::IGNORE,
+        Text(label.into()),
+    )
+}
+
+fn row_node() -> Node {
+    Node {
+        display: Display::Flex,
+        justify_content: JustifyContent::SpaceBetween,
+        width: Val::Percent(100.0),
+        border: UiRect::all(Val::Px(2.0)),
+        padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+        ..default()
+    }
+}
+
+fn setup_controls(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    mut cursor: ResMut<ControlsCursor>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    cursor.0 = 0;
+    *rebinding = Rebinding::default();
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::Controls),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Px(480.0),
+                    top: Val::Px(40.0),
+                    left: Val::Px(80.0),
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 20.),
+                    Text("Controls\n".into()),
+                ));
+                p.spawn(row_node()).with_children(|row| {
+                    row.spawn(text("Action"));
+                    row.spawn(text("Key / Gamepad"));
+                });
+                for (index, action) in Action::ALL.into_iter().enumerate() {
+                    p.spawn((
+                        ControlsRow(index),
+                        row_node(),
+                        BorderColor(CLEAR_COLOR),
+                        BorderRadius::all(Val::Px(8.0)),
+                    ))
+                    .with_children(|row| {
+                        row.spawn(text(action.label()));
+                        row.spawn((Node::default(), Pickable::IGNORE))
+                            .with_children(|binding| {
+                                binding.spawn((ControlsKey(action), text("")));
+                                binding.spawn(text(" / "));
+                                binding.spawn((ControlsButton(action), text("")));
+                            });
+                    })
+                    .observe(set_controls_cursor_on_hover)
+                    .observe(rebind_on_click);
+                }
+                p.spawn((
+                    ControlsRow(RESET_ROW),
+                    row_node(),
+                    BorderColor(CLEAR_COLOR),
+                    BorderRadius::all(Val::Px(8.0)),
+                    children![text("Reset to defaults")],
+                ))
+                .observe(set_controls_cursor_on_hover)
+                .observe(rebind_on_click);
+                p.spawn((ControlsFeedback, text("")));
+            });
+    });
+}
+
+fn set_controls_cursor_on_hover(
+    trigger: Trigger<Pointer<Over>>,
+    rows: Query<&ControlsRow>,
+    rebinding: Res<Rebinding>,
+    mut cursor: ResMut<ControlsCursor>,
+) {
+    if let (Ok(row), None) = (rows.get(trigger.target), rebinding.action) {
+        cursor.0 = row.0;
+    }
+}
+
+fn rebind_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    rows: Query<&ControlsRow>,
+    mut bindings: ResMut<Bindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if let (Ok(row), None) = (rows.get(trigger.target), rebinding.action) {
+        choose_row(row.0, &mut bindings, &mut rebinding);
+    }
+}
+
+fn choose_row(row: usize, bindings: &mut Bindings, rebinding: &mut Rebinding) {
+    rebinding.rejected = None;
+    match Action::ALL.get(row) {
+        Some(action) => rebinding.action = Some(*action),
+        None => {
+            *bindings = Bindings::default();
+            bindings.save();
+        }
+    }
+}
+
+// Takes the next key or button for the action being rebound. Runs first, so the press
+// that chose the action isn't taken as its new binding.
+fn capture_binding(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut gamepads: Query<&mut Gamepad>,
+    key_map: Res<KeyMap>,
+    mut bindings: ResMut<Bindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+
+    let pressed_key = keys.get_just_pressed().next().copied();
+    let pressed_button = gamepads.iter_mut().find_map(|gamepad| {
+        let button = gamepad.get_just_pressed().next().copied()?;
+        Some((gamepad, button))
+    });
+    if let Some(key) = pressed_key {
+        if let Some(rejected) = key_conflict(&bindings, &key_map, action, key) {
+            rebinding.rejected = Some(rejected);
+            return;
+        }
+        keys.clear_just_pressed(key);
+        let binding = Binding {
+            key,
+            ..bindings.get(action)
+        };
+        bindings.0.insert(action, binding);
+    } else if let Some((mut gamepad, button)) = pressed_button {
+        if let Some(rejected) = button_conflict(&bindings, action, button) {
+            rebinding.rejected = Some(rejected);
+            return;
+        }
+        gamepad.digital_mut().clear_just_pressed(button);
+        let binding = Binding {
+            button,
+            ..bindings.get(action)
+        };
+        bindings.0.insert(action, binding);
+    } else {
+        return;
+    }
+
+    bindings.save();
+    *rebinding = Rebinding::default();
+}
+
+// Why the key can't be bound to the action, when it can't
+fn key_conflict(
+    bindings: &Bindings,
+    key_map: &KeyMap,
+    action: Action,
+    key: KeyCode,
+) -> Option<String> {
+    // Any key a prompt can ask for, or Shift held for one, is pressed while playing
+    let typing = key_map.key_for(key, false).is_some() || key_map.key_for(key, true).is_some();
+    if typing || is_modifier(key) {
+        return Some(format!("{} is used for playing", key_label(key)));
+    }
+    bindings
+        .bound_elsewhere(action, |binding| binding.key == key)
+        .map(|other| format!("{} is already {}", key_label(key), other.label()))
+}
+
+// In gamepad play the face buttons, bumpers and D-pad type the prompts
+fn button_conflict(bindings: &Bindings, action: Action, button: GamepadButton) -> Option<String> {
+    if action.while_playing() && is_play_button(button) {
+        return Some(format!("{} is used for playing", button_label(button)));
+    }
+    bindings
+        .bound_elsewhere(action, |binding| binding.button == button)
+        .map(|other| format!("{} is already {}", button_label(button), other.label()))
+}
+
+fn controls_navigation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut interaction_rate_limit: ResMut<InteractionRateLimit>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut bindings: ResMut<Bindings>,
+    mut cursor: ResMut<ControlsCursor>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if rebinding.action.is_some() {
+        return;
+    }
+
+    if bindings.just_pressed(Action::Back, &keyboard_input, &gamepads) {
+        commands.send_event(SceneChange(AppState::Menu));
+        return;
+    }
+
+    if bindings.just_pressed(Action::Confirm, &keyboard_input, &gamepads) {
+        choose_row(cursor.0, &mut bindings, &mut rebinding);
+        return;
+    }
+
+    let up = bindings.pressed(Action::Up, &keyboard_input, &gamepads);
+    let down = bindings.pressed(Action::Down, &keyboard_input, &gamepads);
+    interaction_rate_limit.0.tick(time.delta());
+    if !interaction_rate_limit.0.finished() || !(up || down) {
+        return;
+    }
+    interaction_rate_limit.0.reset();
+
+    cursor.0 = match up {
+        true => cursor.0.saturating_sub(1),
+        false => (cursor.0 + 1).min(RESET_ROW),
+    };
+}
+
+fn update_controls_display(
+    bindings: Res<Bindings>,
+    cursor: Res<ControlsCursor>,
+    rebinding: Res<Rebinding>,
+    mut rows: Query<(&ControlsRow, &mut BorderColor)>,
+    mut key_texts: Query<(&ControlsKey, &mut Text)>,
+    mut button_texts: Query<(&ControlsButton, &mut Text), Without<ControlsKey>>,
+    mut feedback: Query<
+        &mut Text,
+        (
+            With<ControlsFeedback>,
+            Without<ControlsKey>,
+            Without<ControlsButton>,
+        ),
+    >,
+) {
+    for (row, mut border) in &mut rows {
+        border.0 = match row.0 == cursor.0 {
+            true => LIGHT_COLOR,
+            false => CLEAR_COLOR,
+        };
+    }
+    for (key, mut text) in &mut key_texts {
+        text.0 = match rebinding.action == Some(key.0) {
+            true => "...".into(),
+            false => key_label(bindings.key(key.0)),
+        };
+    }
+    for (button, mut text) in &mut button_texts {
+        text.0 = match rebinding.action == Some(button.0) {
+            true => "...".into(),
+            false => button_label(bindings.get(button.0).button),
+        };
+    }
+    if let Ok(mut text) = feedback.single_mut() {
+        text.0 = match (&rebinding.action, &rebinding.rejected) {
+            (Some(_), Some(rejected)) => format!("\n{}, try another", rejected),
+            (Some(action), None) => format!("\nPress a key or button for {}", action.label()),
+            (None, _) => format!(
+                "\n{} to change, {} to go back",
+                key_label(bindings.key(Action::Confirm)),
+                key_label(bindings.key(Action::Back))
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(action: Action, key: KeyCode) -> Option<String> {
+        key_conflict(&Bindings::default(), &KeyMap::default(), action, key)
+    }
+
+    fn button(action: Action, button: GamepadButton) -> Option<String> {
+        button_conflict(&Bindings::default(), action, button)
+    }
+
+    #[test]
+    fn rebinds_to_a_free_key_or_button() {
+        assert_eq!(key(Action::Start, KeyCode::F1), None);
+        assert_eq!(key(Action::Confirm, KeyCode::NumpadEnter), None);
+        assert_eq!(button(Action::Pause, GamepadButton::LeftThumb), None);
+        // Menus can use the buttons that type in gamepad play
+        assert_eq!(button(Action::Confirm, GamepadButton::West), None);
+        // Keeping the binding it already has
+        assert_eq!(key(Action::Start, KeyCode::Space), None);
+        assert_eq!(button(Action::Back, GamepadButton::East), None);
+    }
+
+    #[test]
+    fn rejects_modifiers() {
+        for modifier in [KeyCode::ShiftLeft, KeyCode::ControlRight, KeyCode::AltLeft] {
+            assert!(key(Action::Confirm, modifier).is_some(), "{:?}", modifier);
+        }
+        assert_eq!(
+            key(Action::Start, KeyCode::ShiftRight).as_deref(),
+            Some("ShiftRight is used for playing")
+        );
+    }
+
+    #[test]
+    fn rejects_keys_prompts_ask_for() {
+        assert_eq!(
+            key(Action::Pause, KeyCode::KeyQ).as_deref(),
+            Some("Q is used for playing")
+        );
+        assert_eq!(
+            key(Action::Up, KeyCode::Digit1).as_deref(),
+            Some("1 is used for playing")
+        );
+        assert!(key(Action::Back, KeyCode::Slash).is_some());
+    }
+
+    #[test]
+    fn rejects_play_buttons_while_playing() {
+        for play_button in [
+            GamepadButton::South,
+            GamepadButton::North,
+            GamepadButton::LeftTrigger,
+            GamepadButton::RightTrigger,
+            GamepadButton::DPadLeft,
+        ] {
+            assert!(
+                button(Action::Start, play_button).is_some(),
+                "{:?}",
+                play_button
+            );
+        }
+        assert_eq!(
+            button(Action::Ghost, GamepadButton::South).as_deref(),
+            Some("A is used for playing")
+        );
+    }
+
+    #[test]
+    fn rejects_bindings_taken_on_the_same_screens() {
+        assert_eq!(
+            key(Action::Confirm, KeyCode::Escape).as_deref(),
+            Some("Esc is already Back")
+        );
+        assert_eq!(
+            key(Action::Ghost, KeyCode::Space).as_deref(),
+            Some("Spacebar is already Start")
+        );
+        assert_eq!(
+            button(Action::Confirm, GamepadButton::DPadUp).as_deref(),
+            Some("DPadUp is already Navigate up")
+        );
+        assert_eq!(
+            button(Action::Pause, GamepadButton::Start).as_deref(),
+            Some("Start is already Start")
+        );
+        // Back is for menus and Pause for playing, so they can share Esc
+        assert_eq!(key(Action::Back, KeyCode::Escape), None);
+        assert_eq!(key(Action::Pause, KeyCode::Escape), None);
+    }
+}
@@ -7,8 +7,10 @@ use crate::app::{
 use crate::assets::custom::{ImageAssets, SoundAssets};
 use crate::assets::lexi::game_over::GameOverLex;
 use crate::assets::lexi::levels::{LevelInfo, LevelLex};
+use crate::controls::{Action, Bindings, action_just_pressed};
 use crate::leaderboard::{
-    GetHighScore, HighScoreboardTopFive, LeaderboardLevel, LeaderboardLevelSelected, PostHighScore,
+    GetHighScore, HighScoreSubmissionDisplay, HighScoreboardTopFive, LeaderboardLevel,
+    LeaderboardLevelSelected, PostHighScore, update_high_score_submission,
     update_high_scoreboard_top_five,
 };
 use crate::menu::LeaderboardName;
@@ -16,7 +18,6 @@ use crate::util::handles::BODY_FONT;
 use crate::{camera, game};
 use bevy::ecs::system::Commands;
 use bevy::input::ButtonState;
-use bevy::input::common_conditions::input_just_pressed;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::input::{ButtonInput, keyboard};
 use bevy::math::bounding::{Aabb2d, IntersectsVolume};
@@ -32,9 +33,26 @@ use bevy_simple_text_input::{
     TextInput, TextInputPlugin, TextInputTextColor, TextInputTextFont, TextInputValue,
 };
 use rand::Rng;
-use rand::prelude::IndexedMutRandom;
-use rand::seq::IndexedRandom;
+use rand::seq::{IndexedRandom, SliceRandom};
 use serde::Deserialize;
+use std::time::Duration;
+use weed_core::{
+    CLEAR_WEEDS, InputLog, LevelRules, Outcome, PowerUp, Press, Run, UNKNOWN_KEY, prompt_keys,
+};
+
+mod breakdown;
+mod click_mode;
+mod gamepad;
+mod ghost;
+mod hot_seat;
+mod power_ups;
+mod split_screen;
+mod touch_keyboard;
+mod versus;
+
+pub use click_mode::ClickMode;
+pub use gamepad::is_play_button;
+pub use hot_seat::HotSeat;
 
 const MAX_VISIBLE_WEEDS: u32 = 10;
 const MIN_PLACEMENT_DISTANCE: f32 = 35.0;
@@ -44,7 +62,18 @@ pub(super) fn plugin(app: &mut App) {
         .add_event::<SceneChange>()
         .add_event::<RemoveWeed>()
         .add_event::<GrowWeed>()
-        .add_plugins((HttpClientPlugin))
+        .add_plugins((
+            HttpClientPlugin,
+            breakdown::plugin,
+            click_mode::plugin,
+            gamepad::plugin,
+            ghost::plugin,
+            hot_seat::plugin,
+            power_ups::plugin,
+            split_screen::plugin,
+            touch_keyboard::plugin,
+            versus::plugin,
+        ))
         .insert_resource(LoadedLevel::default())
         .insert_resource(GameTimer::default())
         .insert_resource(GameStatus::default())
@@ -56,6 +85,7 @@ pub(super) fn plugin(app: &mut App) {
         .insert_resource(GlobalMusicFadeTimer::default())
         .insert_resource(TimeSpent::default())
         .insert_resource(PlayerScore::default())
+        .insert_resource(RunRecord::default())
         .insert_resource(DisplayAffirmation::default())
         .insert_resource(Advance::default())
         .insert_resource(KeyMap::default())
@@ -89,6 +119,8 @@ pub(super) fn plugin(app: &mut App) {
                 animate_key,
                 update_healthbar_display,
                 update_letters_remaining_display,
+                update_streak_display,
+                update_weed_type_display,
                 update_rose_grows_display,
                 update_timeboard,
             )
@@ -118,29 +150,39 @@ pub(super) fn plugin(app: &mut App) {
         .add_systems(
             Update,
             load_next_level_on_spacebar.run_if(
-                input_just_pressed(KeyCode::Space)
+                action_just_pressed(Action::Start)
                     .and(in_state(AppState::LoadNextLevel).and(in_state(GameState::NextLevel))),
             ),
         )
         .add_systems(
             Update,
-            (lower_volume_between_levels, update_high_scoreboard_top_five)
+            (
+                lower_volume_between_levels,
+                update_high_scoreboard_top_five,
+                update_high_score_submission,
+            )
                 .run_if(in_state(AppState::LoadNextLevel)),
         )
         .add_systems(OnEnter(AppState::GameOver), setup_game_over)
         .add_systems(
             Update,
             restart_game_at_menu
-                .run_if(input_just_pressed(KeyCode::Space).and(in_state(AppState::GameOver))),
+                .run_if(action_just_pressed(Action::Start).and(in_state(AppState::GameOver))),
         )
         .add_systems(
             Update,
             press_space_to_start.run_if(
                 in_state(GameState::NotRunning)
                     .and(in_state(AppState::Game))
-                    .and(input_just_pressed(KeyCode::Space)),
+                    .and(action_just_pressed(Action::Start)),
             ),
         )
+        .add_systems(
+            Update,
+            toggle_pause.run_if(in_state(AppState::Game).and(action_just_pressed(Action::Pause))),
+        )
+        .add_systems(OnEnter(GameState::Paused), show_paused)
+        .add_systems(OnExit(GameState::Paused), hide_paused)
         .add_systems(Update, music_toggle);
 }
 
@@ -148,6 +190,8 @@ pub(super) fn plugin(app: &mut App) {
 #[states(scoped_entities)]
 pub enum GameState {
     Running,
+    // The clock stops and the prompt is hidden until it's unpaused
+    Paused,
     #[default]
     NotRunning,
     LevelComplete,
@@ -172,6 +216,54 @@ pub fn press_space_to_start(
     }
 }
 
+fn toggle_pause(state: Res<State<GameState>>, mut game_state: ResMut<NextState<GameState>>) {
+    match state.get() {
+        GameState::Running => game_state.set(GameState::Paused),
+        GameState::Paused => game_state.set(GameState::Running),
+        _ => {}
+    }
+}
+
+fn show_paused(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    bindings: Res<Bindings>,
+    mut letterboxes: Query<&mut Visibility, With<Letterbox>>,
+) {
+    for mut visibility in &mut letterboxes {
+        *visibility = Visibility::Hidden;
+    }
+    commands.entity(hud.0).with_children(|parent| {
+        parent.spawn((
+            StateScoped(GameState::Paused),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                width: Val::Percent(100.0),
+                top: Val::Px(200.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![(
+                TextColor(LIGHT_COLOR),
+                TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                TextLayout::default().with_justify(JustifyText::Center),
+                Text(format!(
+                    "Paused\n\n{}",
+                    bindings.prompt(Action::Pause, "Resume")
+                )),
+            )],
+        ));
+    });
+}
+
+fn hide_paused(mut letterboxes: Query<&mut Visibility, With<Letterbox>>) {
+    for mut visibility in &mut letterboxes {
+        *visibility = Visibility::Inherited;
+    }
+}
+
 pub fn global_volume_set(mut volume: ResMut<GlobalVolume>) {
     volume.volume = bevy::audio::Volume::Linear(0.50); // Sets global volume to 50%
 }
@@ -384,7 +476,9 @@ pub fn setup(
     mut active_key: ResMut<ActiveKey>,
     mut time_spent: ResMut<TimeSpent>,
     mut player_score: ResMut<PlayerScore>,
+    mut run_record: ResMut<RunRecord>,
     hide_instructions: Res<HideInstructions>,
+    bindings: Res<Bindings>,
 ) {
     if advance.0 {
         current_level_id.0 += 1;
@@ -482,11 +576,10 @@ pub fn setup(
         return;
     };
 
+    *run_record = RunRecord::new(current_level_id.0);
     loaded_level.0 = Some(Level::new(
-        level_info.letters.clone(),
-        level_info.time,
-        level_info.count,
-        level_info.fail_count,
+        level_info.rules(),
+        run_record.seed,
         level_info.id.parse().unwrap(),
     ));
 
@@ -556,6 +649,20 @@ pub fn setup(
                         .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
                     Text("".into()),
                 ));
+                p.spawn((
+                    StreakDisplay,
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                    Text("".into()),
+                ));
+                p.spawn((
+                    WeedTypeDisplay,
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                    Text("".into()),
+                ));
             });
 
         parent.spawn((
@@ -662,7 +769,7 @@ pub fn setup(
                         TextColor(LIGHT_COLOR),
                         TextFont::from_font(BODY_FONT)
                             .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
-                        Text("Press Spacebar to Start".into()),
+                        Text(bindings.prompt(Action::Start, "Start")),
                     ));
                 });
         }
@@ -675,6 +782,7 @@ pub fn setup(
     let mut weed_x_placements = Vec::new();
 
     weeds_left.max = MAX_VISIBLE_WEEDS.min(level_info.count);
+    weeds_left.sprites = level_info.weed_sprites();
 
     while weed_x_placements.len() < weeds_left.max as usize {
         let candidate = if rng.random_bool(0.5) {
@@ -691,28 +799,7 @@ pub fn setup(
         }
     }
 
-    let weeds = vec![
-        (
-            image_assets.weed1.clone(),
-            image_assets.weed1_layout.clone(),
-        ),
-        (
-            image_assets.weed2.clone(),
-            image_assets.weed2_layout.clone(),
-        ),
-        (
-            image_assets.weed3.clone(),
-            image_assets.weed3_layout.clone(),
-        ),
-        (
-            image_assets.weed4.clone(),
-            image_assets.weed4_layout.clone(),
-        ),
-        (
-            image_assets.weed5.clone(),
-            image_assets.weed5_layout.clone(),
-        ),
-    ];
+    let weeds = weed_images(&image_assets, &weeds_left.sprites);
     for x in weed_x_placements {
         let image = weeds.choose(&mut rng).unwrap();
 
@@ -791,8 +878,10 @@ fn instructions_box_animation(
     time: Res<Time>,
     mut instructions_query: Query<(&mut Sprite, &mut Instructions)>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
 ) {
-    let spacebar_pressed = keyboard_input.just_pressed(KeyCode::Space);
+    let spacebar_pressed = bindings.just_pressed(Action::Start, &keyboard_input, &gamepads);
     if let Ok((mut sprite, mut instructions)) = instructions_query.single_mut() {
         instructions.screen_change_wait_timer.tick(time.delta());
 
@@ -845,7 +934,7 @@ fn instructions_box_animation(
                             TextColor(LIGHT_COLOR),
                             TextFont::from_font(BODY_FONT)
                                 .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
-                            Text("Press Spacebar to Start".into()),
+                            Text(bindings.prompt(Action::Start, "Start")),
                         ));
                     });
             });
@@ -901,17 +990,57 @@ pub struct LettersRemainingDisplay;
 #[derive(Component)]
 pub struct HealthbarDisplay;
 
+#[derive(Component)]
+pub struct StreakDisplay;
+
+#[derive(Component)]
+pub struct WeedTypeDisplay;
+
 #[derive(Resource, Default)]
 pub struct TimeSpent(pub HashMap<usize, f32>);
 
 #[derive(Resource, Default)]
 pub struct PlayerScore(pub HashMap<usize, u32>);
 
-#[derive(Resource, Default)]
+// Everything the leaderboard needs to replay a level: the seed the prompts were
+// picked with and every key pressed, timed from when the level started running
+#[derive(Resource)]
+pub struct RunRecord {
+    pub level: usize,
+    pub seed: u64,
+    pub inputs: InputLog,
+    elapsed: Duration,
+}
+
+impl Default for RunRecord {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RunRecord {
+    pub fn new(level: usize) -> Self {
+        Self {
+            level,
+            seed: rand::random(),
+            inputs: InputLog::default(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    // The run rules work in whole milliseconds so a replay makes the same decisions
+    pub fn now_ms(&self) -> u32 {
+        self.elapsed.as_millis() as u32
+    }
+}
+
+#[derive(Resource, Component, Default)]
 pub struct WeedTracker {
     pub max: u32,
     pub visible: u32,
     pub non_visible: u32,
+    // Which of weed1 to weed5 the level's weed types are drawn with, all when empty
+    pub sprites: Vec<usize>,
 }
 
 impl WeedTracker {
@@ -923,6 +1052,44 @@ impl WeedTracker {
     }
 }
 
+fn weed_images(
+    image_assets: &ImageAssets,
+    sprites: &[usize],
+) -> Vec<(Handle<Image>, Handle<TextureAtlasLayout>)> {
+    let weeds = vec![
+        (
+            image_assets.weed1.clone(),
+            image_assets.weed1_layout.clone(),
+        ),
+        (
+            image_assets.weed2.clone(),
+            image_assets.weed2_layout.clone(),
+        ),
+        (
+            image_assets.weed3.clone(),
+            image_assets.weed3_layout.clone(),
+        ),
+        (
+            image_assets.weed4.clone(),
+            image_assets.weed4_layout.clone(),
+        ),
+        (
+            image_assets.weed5.clone(),
+            image_assets.weed5_layout.clone(),
+        ),
+    ];
+    let chosen = weeds
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| sprites.contains(&(index + 1)))
+        .map(|(_, weed)| weed.clone())
+        .collect::<Vec<_>>();
+    match chosen.is_empty() {
+        true => weeds,
+        false => chosen,
+    }
+}
+
 #[derive(Component)]
 pub struct Weed {
     pub plant_growth: PlantGrowth,
@@ -1000,44 +1167,32 @@ pub struct CurrentLevelId(pub usize);
 #[derive(Resource, Default)]
 pub struct LoadedLevel(Option<Level>);
 
-#[derive(Debug)]
+#[derive(Component, Debug)]
 pub struct Level {
     _id: usize,
-    pub letters: Vec<String>,
-    pub timer: Timer,
-    pub countdown: u32,
-    pub max_fail: u32,
-    pub fail_count: u32,
+    // Prompts, the letter timer and wrong keys are decided by the shared rules so the
+    // leaderboard can replay the level
+    pub run: Run,
 }
 
 impl Default for Level {
     fn default() -> Self {
-        Self {
-            _id: 0,
-            letters: vec![],
-            timer: Timer::from_seconds(5.0, TimerMode::Once),
-            countdown: 0,
-            fail_count: 0,
-            max_fail: 0,
-        }
+        Self::new(LevelRules::default(), 0, 0)
     }
 }
 
 impl Level {
-    pub fn new(letters: Vec<String>, seconds: f32, count: u32, fail_count: u32, id: usize) -> Self {
+    pub fn new(rules: LevelRules, seed: u64, id: usize) -> Self {
         Self {
             _id: id,
-            letters: letters,
-            timer: Timer::from_seconds(seconds, TimerMode::Once),
-            countdown: count,
-            fail_count: 0,
-            max_fail: fail_count,
+            run: Run::new(rules, seed),
         }
     }
 }
 
-#[derive(Resource)]
-pub struct ActiveKey(Option<(KeyCode)>);
+// The prompt showing, if any
+#[derive(Resource, Component)]
+pub struct ActiveKey(Option<String>);
 
 impl Default for ActiveKey {
     fn default() -> Self {
@@ -1050,61 +1205,135 @@ impl ActiveKey {
         self.0 = None;
     }
 
-    pub fn set_random(&mut self, key_map: &KeyMap, level_info: &Level) -> Result<char, ()> {
-        let mut rng = rand::rng();
-
-        let chars = &level_info.letters;
+    pub fn set(&mut self, prompt: &str) {
+        self.0 = Some(prompt.to_string());
+    }
+}
 
-        let Some(s) = chars.choose(&mut rng) else {
-            warn!(?chars, "failed setting char");
-            self.reset();
-            return Err(());
-        };
+// Every character a prompt can ask for, the key that types it and whether Shift is
+// held for it. Laid out as a US keyboard.
+#[derive(Resource)]
+pub struct KeyMap(HashMap<char, (KeyCode, bool)>);
 
-        let c = s.chars().next().unwrap();
+impl KeyMap {
+    pub fn key_for(&self, key_code: KeyCode, shift: bool) -> Option<char> {
+        self.0
+            .iter()
+            .find(|(_, key)| **key == (key_code, shift))
+            .map(|(c, _)| *c)
+    }
 
-        // let c = rng.random_range(b'a'..=b'z') as char;
-        self.0 = key_map.0.get(&c).cloned();
-        Ok(c)
+    // The character on the same key without Shift, 'a' for 'A' and '1' for '!'
+    pub fn base(&self, c: char) -> Option<char> {
+        let (key_code, _) = self.0.get(&c)?;
+        self.key_for(*key_code, false)
     }
 }
 
-#[derive(Resource)]
-pub struct KeyMap(HashMap<char, KeyCode>);
+// Held for capitals and shifted punctuation, they don't count as key presses
+pub fn is_modifier(key_code: KeyCode) -> bool {
+    matches!(
+        key_code,
+        KeyCode::ShiftLeft
+            | KeyCode::ShiftRight
+            | KeyCode::ControlLeft
+            | KeyCode::ControlRight
+            | KeyCode::AltLeft
+            | KeyCode::AltRight
+            | KeyCode::SuperLeft
+            | KeyCode::SuperRight
+    )
+}
 
 impl Default for KeyMap {
     fn default() -> Self {
+        let unshifted = [
+            ('a', KeyCode::KeyA),
+            ('b', KeyCode::KeyB),
+            ('c', KeyCode::KeyC),
+            ('d', KeyCode::KeyD),
+            ('e', KeyCode::KeyE),
+            ('f', KeyCode::KeyF),
+            ('g', KeyCode::KeyG),
+            ('h', KeyCode::KeyH),
+            ('i', KeyCode::KeyI),
+            ('j', KeyCode::KeyJ),
+            ('k', KeyCode::KeyK),
+            ('l', KeyCode::KeyL),
+            ('m', KeyCode::KeyM),
+            ('n', KeyCode::KeyN),
+            ('o', KeyCode::KeyO),
+            ('p', KeyCode::KeyP),
+            ('q', KeyCode::KeyQ),
+            ('r', KeyCode::KeyR),
+            ('s', KeyCode::KeyS),
+            ('t', KeyCode::KeyT),
+            ('u', KeyCode::KeyU),
+            ('v', KeyCode::KeyV),
+            ('w', KeyCode::KeyW),
+            ('x', KeyCode::KeyX),
+            ('y', KeyCode::KeyY),
+            ('z', KeyCode::KeyZ),
+            ('1', KeyCode::Digit1),
+            ('2', KeyCode::Digit2),
+            ('3', KeyCode::Digit3),
+            ('4', KeyCode::Digit4),
+            ('5', KeyCode::Digit5),
+            ('6', KeyCode::Digit6),
+            ('7', KeyCode::Digit7),
+            ('8', KeyCode::Digit8),
+            ('9', KeyCode::Digit9),
+            ('0', KeyCode::Digit0),
+            ('-', KeyCode::Minus),
+            ('=', KeyCode::Equal),
+            ('[', KeyCode::BracketLeft),
+            (']', KeyCode::BracketRight),
+            ('\\', KeyCode::Backslash),
+            (';', KeyCode::Semicolon),
+            ('\'', KeyCode::Quote),
+            (',', KeyCode::Comma),
+            ('.', KeyCode::Period),
+            ('/', KeyCode::Slash),
+            ('`', KeyCode::Backquote),
+        ];
+        let shifted = [
+            ('!', KeyCode::Digit1),
+            ('@', KeyCode::Digit2),
+            ('#', KeyCode::Digit3),
+            ('$', KeyCode::Digit4),
+            ('%', KeyCode::Digit5),
+            ('^', KeyCode::Digit6),
+            ('&', KeyCode::Digit7),
+            ('*', KeyCode::Digit8),
+            ('(', KeyCode::Digit9),
+            (')', KeyCode::Digit0),
+            ('_', KeyCode::Minus),
+            ('+', KeyCode::Equal),
+            ('{', KeyCode::BracketLeft),
+            ('}', KeyCode::BracketRight),
+            ('|', KeyCode::Backslash),
+            (':', KeyCode::Semicolon),
+            ('"', KeyCode::Quote),
+            ('<', KeyCode::Comma),
+            ('>', KeyCode::Period),
+            ('?', KeyCode::Slash),
+            ('~', KeyCode::Backquote),
+        ];
+        let capitals = unshifted
+            .iter()
+            .filter(|(c, _)| c.is_ascii_lowercase())
+            .map(|(c, key_code)| (c.to_ascii_uppercase(), *key_code));
+
         Self(
-            [
-                ('a', KeyCode::KeyA),
-                ('b', KeyCode::KeyB),
-                ('c', KeyCode::KeyC),
-                ('d', KeyCode::KeyD),
-                ('e', KeyCode::KeyE),
-                ('f', KeyCode::KeyF),
-                ('g', KeyCode::KeyG),
-                ('h', KeyCode::KeyH),
-                ('i', KeyCode::KeyI),
-                ('j', KeyCode::KeyJ),
-                ('k', KeyCode::KeyK),
-                ('l', KeyCode::KeyL),
-                ('m', KeyCode::KeyM),
-                ('n', KeyCode::KeyN),
-                ('o', KeyCode::KeyO),
-                ('p', KeyCode::KeyP),
-                ('q', KeyCode::KeyQ),
-                ('r', KeyCode::KeyR),
-                ('s', KeyCode::KeyS),
-                ('t', KeyCode::KeyT),
-                ('u', KeyCode::KeyU),
-                ('v', KeyCode::KeyV),
-                ('w', KeyCode::KeyW),
-                ('x', KeyCode::KeyX),
-                ('y', KeyCode::KeyY),
-                ('z', KeyCode::KeyZ),
-            ]
-            .into_iter()
-            .collect(),
+            unshifted
+                .iter()
+                .map(|(c, key_code)| (*c, (*key_code, false)))
+                .chain(
+                    capitals
+                        .chain(shifted)
+                        .map(|(c, key_code)| (c, (key_code, true))),
+                )
+                .collect(),
         )
     }
 }
@@ -1128,6 +1357,11 @@ impl Default for KeyPosition {
         let x_end_bottom = RESOLUTION_WIDTH / 2. - 100.;
         let y_bottom = y_middle - 65.0;
 
+        // Digits above the letters, punctuation in a row of its own below them
+        let y_digits = y_top + 65.0;
+        let x_start_symbols = -RESOLUTION_WIDTH / 2. + 50.;
+        let y_symbols = y_bottom - 65.0;
+
         let map = [
             ('a', Vec2::new(x_start_middle + key_size * 0., y_middle)),
             ('s', Vec2::new(x_start_middle + key_size * 1., y_middle)),
@@ -1155,8 +1389,29 @@ impl Default for KeyPosition {
             ('b', Vec2::new(x_start_bottom + key_size * 4., y_bottom)),
             ('n', Vec2::new(x_end_bottom - key_size * 1., y_bottom)),
             ('m', Vec2::new(x_end_bottom - key_size * 0., y_bottom)),
+            ('1', Vec2::new(x_start_top + key_size * 0., y_digits)),
+            ('2', Vec2::new(x_start_top + key_size * 1., y_digits)),
+            ('3', Vec2::new(x_start_top + key_size * 2., y_digits)),
+            ('4', Vec2::new(x_start_top + key_size * 3., y_digits)),
+            ('5', Vec2::new(x_start_top + key_size * 4., y_digits)),
+            ('6', Vec2::new(x_end_top - key_size * 4., y_digits)),
+            ('7', Vec2::new(x_end_top - key_size * 3., y_digits)),
+            ('8', Vec2::new(x_end_top - key_size * 2., y_digits)),
+            ('9', Vec2::new(x_end_top - key_size * 1., y_digits)),
+            ('0', Vec2::new(x_end_top - key_size * 0., y_digits)),
         ]
         .into_iter()
+        .chain(
+            ['`', '-', '=', '[', ']', '\\', ';', '\'', ',', '.', '/']
+                .into_iter()
+                .enumerate()
+                .map(|(i, key)| {
+                    (
+                        key,
+                        Vec2::new(x_start_symbols + key_size * i as f32, y_symbols),
+                    )
+                }),
+        )
         .map(|(key, value)| (key, value.extend(0.0)))
         .collect();
 
@@ -1164,6 +1419,23 @@ impl Default for KeyPosition {
     }
 }
 
+impl KeyPosition {
+    // Where a prompt's letterbox goes, between its keys for a chord
+    pub fn of(&self, key_map: &KeyMap, prompt: &str) -> Option<Vec3> {
+        let positions = prompt_keys(prompt)
+            .into_iter()
+            .map(|key| {
+                let base = key_map.base(key).unwrap_or(key);
+                self.0.get(&base).copied()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        match positions.is_empty() {
+            true => None,
+            false => Some(positions.iter().sum::<Vec3>() / positions.len() as f32),
+        }
+    }
+}
+
 #[derive(Component, Clone)]
 pub struct AffirmationMarker {
     pub timer: Timer,
@@ -1190,13 +1462,26 @@ impl DisplayAffirmation {
     }
 }
 
+// Pop-ups for correct keys, one list per streak multiplier, each getting louder
 #[derive(Resource)]
-pub struct Affirmations(VecDeque<String>);
+pub struct Affirmations(Vec<VecDeque<String>>);
+
+impl Affirmations {
+    // Takes turns through the list for the multiplier, the top list covers anything
+    // higher
+    pub fn next(&mut self, multiplier: u32) -> String {
+        let tier = (multiplier.max(1) as usize - 1).min(self.0.len() - 1);
+        let affirmations = &mut self.0[tier];
+        let affirmation = affirmations.pop_front().unwrap_or("Unbeleafable!".into());
+        affirmations.push_back(affirmation.clone());
+        affirmation
+    }
+}
 
 impl Default for Affirmations {
     fn default() -> Self {
-        Self(
-            vec![
+        let tiers: [&[&str]; 4] = [
+            &[
                 "That's unbeleafable!",
                 "Totally radish!",
                 "Soil-shaking!",
@@ -1205,10 +1490,31 @@ impl Default for Affirmations {
                 "Shear brilliance!",
                 "Fernomenal",
                 "Plantastic",
-            ]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<VecDeque<_>>(),
+            ],
+            &[
+                "On a roll!",
+                "Lettuce celebrate!",
+                "Hoe-ly moly!",
+                "Weed whacker!",
+            ],
+            &[
+                "Mow-mentum!",
+                "Unstoppabloom!",
+                "Thyme machine!",
+                "Grass-kicking!",
+            ],
+            &[
+                "LEGENDARY GARDENER!",
+                "Root of all awesome!",
+                "Photosynthesis speedrun!",
+                "Botanical beast!",
+            ],
+        ];
+        Self(
+            tiers
+                .iter()
+                .map(|tier| tier.iter().map(|s| s.to_string()).collect::<VecDeque<_>>())
+                .collect(),
         )
     }
 }
@@ -1216,8 +1522,10 @@ impl Default for Affirmations {
 pub fn level_timer_counter(
     time: Res<Time>,
     mut time_spent: ResMut<TimeSpent>,
+    mut run_record: ResMut<RunRecord>,
     current_level_id: Res<CurrentLevelId>,
 ) {
+    run_record.elapsed += time.delta();
     if let Some(timer) = time_spent.0.get_mut(&current_level_id.0) {
         *timer += time.delta_secs();
     } else {
@@ -1225,24 +1533,53 @@ pub fn level_timer_counter(
     }
 }
 
-// Countdown the letter timer and trigger game over
+// Countdown the letter timer and trigger game over, or grow another weed when a
+// spreader's letter runs out
 pub fn level_timer_countdown(
     mut commands: Commands,
-    time: Res<Time>,
+    run_record: Res<RunRecord>,
     mut current_level: ResMut<LoadedLevel>,
     mut game_status: ResMut<GameStatus>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut active_key: ResMut<ActiveKey>,
+    key_map: Res<KeyMap>,
+    key_position: Res<KeyPosition>,
+    image_assets: Res<ImageAssets>,
+    mut letterboxes: Query<&mut Letterbox>,
+    mut weeds_left: ResMut<WeedTracker>,
+    mut spreads_seen: Local<u32>,
 ) {
     let Some(level) = current_level.0.as_mut() else {
         return;
     };
-    level.timer.tick(time.delta());
 
-    if level.timer.finished() {
+    if level.run.tick(run_record.now_ms()) == Outcome::TimedOut {
         *game_status = GameStatus::Lose;
         game_state.set(GameState::NotRunning);
         commands.send_event(SceneChange(AppState::GameOver));
     }
+
+    // A new level starts back at zero
+    let spread_count = level.run.spread_count();
+    if spread_count > *spreads_seen {
+        weeds_left.non_visible += spread_count - *spreads_seen;
+        commands.send_event(GrowWeed);
+
+        for mut letterbox in letterboxes.iter_mut() {
+            letterbox.state = ActiveKeyMarker::Out;
+        }
+        if let Some(next_key) = level.run.prompt() {
+            active_key.set(next_key);
+        }
+        spawn_letterbox(
+            &mut commands,
+            &image_assets,
+            &key_map,
+            &key_position,
+            &level.run,
+        );
+    }
+    *spreads_seen = spread_count;
 }
 
 pub fn keypress_events(
@@ -1259,51 +1596,65 @@ pub fn keypress_events(
     mut weeds_left: ResMut<WeedTracker>,
     mut game_state: ResMut<NextState<GameState>>,
     mut sfx_music_volume: ResMut<SfxMusicVolume>,
+    mut run_record: ResMut<RunRecord>,
     sound_assets: Res<SoundAssets>,
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<Bindings>),
 ) {
     for event in events.read() {
         let Some(level) = current_level.0.as_mut() else {
             return;
         };
 
-        let Some(target) = active_key.0 else {
-            let level_over = weeds_left.visible == 0 || level.fail_count == level.max_fail;
+        if active_key.0.is_none() {
+            // The run picks its first prompt up front, it shows on the first key event
+            let level_over = !level.run.is_playing();
             if level_over {
                 info!("Go away, game is over");
                 active_key.reset();
             } else {
-                if let Ok(next_key) = active_key.set_random(&key_map, &level) {
-                    let position = key_position
-                        .0
-                        .get(&next_key)
-                        .cloned()
-                        .unwrap_or(Vec3::new(1000.0, 1000.0, -1000.0));
-
-                    commands.spawn((
-                        StateScoped(AppState::Game),
-                        Letterbox::new(next_key),
-                        Transform::from_translation(position),
-                        Sprite {
-                            image: image_assets.letterbox.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: image_assets.letterbox_layout.clone(),
-                                index: 0,
-                            }),
-                            ..default()
-                        },
-                    ));
-                };
+                if let Some(next_key) = level.run.prompt() {
+                    active_key.set(next_key);
+                }
+                spawn_letterbox(
+                    &mut commands,
+                    &image_assets,
+                    &key_map,
+                    &key_position,
+                    &level.run,
+                );
             }
             return;
-        };
+        }
         if event.state != ButtonState::Pressed {
             continue;
         }
         // info!(?event);
 
-        let pressed_keycode = event.key_code;
+        // Pausing isn't a wrong key
+        if is_modifier(event.key_code) || event.key_code == bindings.key(Action::Pause) {
+            continue;
+        }
+        // Keys that can't be in a prompt still count as wrong
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let key = key_map
+            .key_for(event.key_code, shift)
+            .unwrap_or(UNKNOWN_KEY);
+        let now = run_record.now_ms();
+        // A tough weed stays put until its last letter
+        let clears_weed = level.run.hits_left() <= 1;
+        let weeds_before = level.run.weeds_left();
+        let power_ups_used = level.run.power_ups().len();
+        let press = level.run.press(now, key);
+        let power_up = level.run.power_ups().get(power_ups_used).map(|(p, _)| *p);
+        if press != Press::Ignored {
+            run_record.inputs.push(now, key);
+            commands.send_event(versus::RaceProgress {
+                weeds_left: level.run.weeds_left(),
+                fail_count: level.run.fail_count(),
+            });
+        }
 
-        if target == pressed_keycode {
+        if press == Press::Correct {
             let vol = if sfx_music_volume.sfx { 2.0 } else { 0.0 };
 
             commands.spawn((
@@ -1314,13 +1665,26 @@ pub fn keypress_events(
             for mut letterbox in letterboxes.iter_mut() {
                 letterbox.state = ActiveKeyMarker::Out;
             }
-            let affimation = affirmations.0.pop_front().unwrap_or("Unbeleafable!".into());
-            affirmations.0.push_back(affimation.clone());
-            level.timer.reset();
+            let mut affimation = affirmations.next(level.run.multiplier());
+            let mut weeds_cleared = clears_weed as u32;
+            if let Some(power_up) = power_up {
+                affimation = format!("{}!", power_up.label());
+                // The run takes the extra weeds off straight away. Shown ones are
+                // wacked and the rest come off the ones still to grow, so the garden
+                // keeps matching the run's count.
+                if power_up == PowerUp::ClearWeeds {
+                    let extra = (CLEAR_WEEDS - 1).min(weeds_before);
+                    let shown = weeds_left.visible.saturating_sub(1).min(extra);
+                    weeds_left.non_visible = weeds_left.non_visible.saturating_sub(extra - shown);
+                    weeds_cleared = 1 + shown;
+                }
+            }
             if weeds_left.visible > 0 {
-                commands.send_event(RemoveWeed);
+                for _ in 0..weeds_cleared {
+                    commands.send_event(RemoveWeed);
+                }
             }
-            if weeds_left.visible == 0 {
+            if level.run.outcome() == Outcome::Complete {
                 // Move on to next level
 
                 active_key.reset();
@@ -1331,29 +1695,18 @@ pub fn keypress_events(
                 // commands.send_event(SceneChange(AppState::LoadNextLevel));
             } else {
                 display_affirmation.0 = Some(affimation);
-                if let Ok(next_key) = active_key.set_random(&key_map, &level) {
-                    let position = key_position
-                        .0
-                        .get(&next_key)
-                        .cloned()
-                        .unwrap_or(Vec3::new(1000.0, 1000.0, -1000.0));
-
-                    commands.spawn((
-                        StateScoped(AppState::Game),
-                        Letterbox::new(next_key),
-                        Transform::from_translation(position),
-                        Sprite {
-                            image: image_assets.letterbox.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: image_assets.letterbox_layout.clone(),
-                                index: 0,
-                            }),
-                            ..default()
-                        },
-                    ));
-                };
+                if let Some(next_key) = level.run.prompt() {
+                    active_key.set(next_key);
+                }
+                spawn_letterbox(
+                    &mut commands,
+                    &image_assets,
+                    &key_map,
+                    &key_position,
+                    &level.run,
+                );
             }
-        } else {
+        } else if press == Press::Wrong {
             //
             // render a permanent weed
             //
@@ -1365,8 +1718,7 @@ pub fn keypress_events(
             ));
             commands.send_event(RemoveWeed);
             weeds_left.non_visible += 1;
-            level.fail_count += 1;
-            if level.fail_count >= level.max_fail {
+            if level.run.outcome() == Outcome::TooManyWrongKeys {
                 active_key.reset();
                 // info!("Lose");
                 commands.send_event(SceneChange(AppState::GameOver));
@@ -1409,12 +1761,23 @@ fn weed_animation(
     }
 }
 
-fn wack_weed(mut commands: Commands, mut weeds: Query<&mut Weed>) {
+fn wack_weed(
+    mut commands: Commands,
+    mut events: EventReader<RemoveWeed>,
+    mut weeds: Query<&mut Weed>,
+) {
     let mut rng = rand::rng();
     let mut unwacked: Vec<_> = weeds.iter_mut().filter(|weed| !weed.is_wacked()).collect();
+    unwacked.shuffle(&mut rng);
 
-    if let Some(mut weed) = unwacked.choose_mut(&mut rng) {
-        weed.plant_growth = PlantGrowth::Wacked;
+    let mut wacked = false;
+    for _ in events.read() {
+        if let Some(mut weed) = unwacked.pop() {
+            weed.plant_growth = PlantGrowth::Wacked;
+            wacked = true;
+        }
+    }
+    if wacked {
         commands.send_event(GrowWeed);
     }
 }
@@ -1442,82 +1805,53 @@ fn grow_weed(
         .map(|(transform, _)| transform.translation.x)
         .collect::<Vec<_>>();
 
-    if weed_tracker.non_visible > 0 && visible_weed_count <= weed_tracker.max as usize - 1 {
-        let weeds_to_place = if visible_weed_count == weed_tracker.max as usize - 1 {
-            1
-        } else {
-            if visible_weed_count as u32 + weed_tracker.non_visible <= weed_tracker.max {
-                weed_tracker.non_visible
+    // As many as there's room for, several spreaders can spread in the same frame
+    let room = (weed_tracker.max as usize).saturating_sub(visible_weed_count);
+    let weeds_to_place = room.min(weed_tracker.non_visible as usize);
+
+    // Spawn a bunch of weeds that will later start diminishing
+    // as the user whacks them by pressing the correct keys
+    for _ in 0..weeds_to_place {
+        let mut x: f32;
+        loop {
+            x = if rng.random_bool(0.5) {
+                rng.random_range(-RESOLUTION_WIDTH / 2.0..=-50.0)
             } else {
-                5.min(weed_tracker.non_visible)
+                rng.random_range(50.0..=RESOLUTION_WIDTH / 2.0)
+            };
+
+            if current_placements
+                .iter()
+                .all(|weed_x| (weed_x - x).abs() >= MIN_PLACEMENT_DISTANCE)
+            {
+                break;
             }
-        };
+        }
 
-        // Spawn a bunch of weeds that will later start diminishing
-        // as the user whacks them by pressing the correct keys
-        for _ in 0..weeds_to_place {
-            let mut x: f32;
-            loop {
-                x = if rng.random_bool(0.5) {
-                    rng.random_range(-RESOLUTION_WIDTH / 2.0..=-50.0)
-                } else {
-                    rng.random_range(50.0..=RESOLUTION_WIDTH / 2.0)
-                };
+        let weeds = weed_images(&image_assets, &weed_tracker.sprites);
 
-                if current_placements
-                    .iter()
-                    .all(|weed_x| (weed_x - x as f32).abs() >= MIN_PLACEMENT_DISTANCE)
-                {
-                    break;
-                }
-            }
+        let image = weeds.choose(&mut rng).unwrap();
 
-            let weeds = vec![
-                (
-                    image_assets.weed1.clone(),
-                    image_assets.weed1_layout.clone(),
-                ),
-                (
-                    image_assets.weed2.clone(),
-                    image_assets.weed2_layout.clone(),
-                ),
-                (
-                    image_assets.weed3.clone(),
-                    image_assets.weed3_layout.clone(),
-                ),
-                (
-                    image_assets.weed4.clone(),
-                    image_assets.weed4_layout.clone(),
-                ),
-                (
-                    image_assets.weed5.clone(),
-                    image_assets.weed5_layout.clone(),
-                ),
-            ];
-
-            let image = weeds.choose(&mut rng).unwrap();
-
-            let flip_x = rng.random_bool(0.5);
+        let flip_x = rng.random_bool(0.5);
 
-            commands.spawn((
-                StateScoped(AppState::Game),
-                Weed::new(),
-                Transform::from_translation(Vec3::new(x, -20.0, 0.0)),
-                Sprite {
-                    flip_x: flip_x,
-                    image: image.0.clone(),
-                    texture_atlas: Some(TextureAtlas {
-                        layout: image.1.clone(),
-                        index: 0,
-                    }),
-                    ..default()
-                },
-            ));
+        commands.spawn((
+            StateScoped(AppState::Game),
+            Weed::new(),
+            Transform::from_translation(Vec3::new(x, -20.0, 0.0)),
+            Sprite {
+                flip_x,
+                image: image.0.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: image.1.clone(),
+                    index: 0,
+                }),
+                ..default()
+            },
+        ));
 
-            visible_weed_count += 1;
-            if weed_tracker.non_visible > 0 {
-                weed_tracker.non_visible -= 1;
-            }
+        visible_weed_count += 1;
+        if weed_tracker.non_visible > 0 {
+            weed_tracker.non_visible -= 1;
         }
     }
     weed_tracker.visible = visible_weed_count as u32;
@@ -1555,40 +1889,103 @@ fn animate_key(
                 }
 
                 if atlas.index == 3 {
-                    commands.entity(entity).with_children(|p| {
-                        p.spawn(Sprite {
-                            image: image_assets.letters.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: image_assets.letters_layout.clone(),
-                                index: letterbox.key_index,
-                            }),
-                            ..default()
+                    commands
+                        .entity(entity)
+                        .with_children(|p| match &letterbox.glyph {
+                            Glyph::Letter(index) => {
+                                p.spawn(Sprite {
+                                    image: image_assets.letters.clone(),
+                                    texture_atlas: Some(TextureAtlas {
+                                        layout: image_assets.letters_layout.clone(),
+                                        index: *index,
+                                    }),
+                                    ..default()
+                                });
+                            }
+                            // Chords are wider, so smaller
+                            Glyph::Text(text) => {
+                                let size = if text.chars().count() > 1 { 14.0 } else { 24.0 };
+                                p.spawn((
+                                    Text2d::new(text.clone()),
+                                    TextFont::from_font(BODY_FONT).with_font_size(size),
+                                    TextColor(DARK_COLOR),
+                                    Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+                                ));
+                            }
                         });
-                    });
                 }
             }
         }
     }
 }
 
+// The letterbox for the run's prompt, golden when typing it gives a power-up
+fn spawn_letterbox(
+    commands: &mut Commands,
+    image_assets: &ImageAssets,
+    key_map: &KeyMap,
+    key_position: &KeyPosition,
+    run: &Run,
+) {
+    let Some(prompt) = run.prompt() else {
+        return;
+    };
+    let position = key_position
+        .of(key_map, prompt)
+        .unwrap_or(Vec3::new(1000.0, 1000.0, -1000.0));
+
+    commands.spawn((
+        StateScoped(AppState::Game),
+        Letterbox::new(prompt),
+        Transform::from_translation(position),
+        Sprite {
+            image: image_assets.letterbox.clone(),
+            color: match run.golden() {
+                Some(_) => power_ups::GOLDEN,
+                None => Color::WHITE,
+            },
+            texture_atlas: Some(TextureAtlas {
+                layout: image_assets.letterbox_layout.clone(),
+                index: 0,
+            }),
+            ..default()
+        },
+    ));
+}
+
 #[derive(Component)]
 pub struct Letterbox {
     pub timer: Timer,
     pub state: ActiveKeyMarker,
-    pub key_index: usize,
+    pub prompt: String,
+    pub glyph: Glyph,
+}
+
+// What's drawn in a letterbox. The letters sheet only has a to z, anything else is
+// written out in the body font.
+pub enum Glyph {
+    Letter(usize),
+    Text(String),
 }
 
 impl Letterbox {
-    pub fn new(c: char) -> Self {
-        let idx = match c.to_digit(36) {
-            Some(d) => d - 10,
-            None => 0,
+    pub fn new(prompt: &str) -> Self {
+        let keys = prompt_keys(prompt);
+        let glyph = match keys[..] {
+            [c] if c.is_ascii_lowercase() => Glyph::Letter(c as usize - 'a' as usize),
+            _ => Glyph::Text(
+                keys.iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join("+"),
+            ),
         };
 
         Self {
             timer: Timer::from_seconds(0.06, TimerMode::Repeating),
             state: ActiveKeyMarker::In,
-            key_index: idx as usize,
+            prompt: prompt.to_string(),
+            glyph,
         }
     }
 }
@@ -1671,6 +2068,47 @@ fn update_letters_remaining_display(
     letters_remaining_text.0 = format!("Left: {}", weed_tracker.total());
 }
 
+fn update_streak_display(
+    loaded_level: Res<LoadedLevel>,
+    mut streak: Query<&mut Text, With<StreakDisplay>>,
+) {
+    let (Ok(mut text), Some(level)) = (streak.single_mut(), loaded_level.0.as_ref()) else {
+        return;
+    };
+
+    text.0 = match level.run.streak() {
+        0 => String::new(),
+        n => format!("Streak: {} x{}", n, level.run.multiplier()),
+    };
+}
+
+// Names the weed the letter belongs to and what it does, nothing for a plain weed
+fn update_weed_type_display(
+    loaded_level: Res<LoadedLevel>,
+    mut weed_type: Query<&mut Text, With<WeedTypeDisplay>>,
+) {
+    let (Ok(mut text), Some(level)) = (weed_type.single_mut(), loaded_level.0.as_ref()) else {
+        return;
+    };
+
+    let weed = level.run.weed();
+    if weed.is_plain() {
+        text.0 = String::new();
+        return;
+    }
+    let mut traits = vec![];
+    if weed.hits > 1 {
+        traits.push(format!("{} hits left", level.run.hits_left()));
+    }
+    if weed.spreads {
+        traits.push("spreads".to_string());
+    }
+    if weed.miss_cost > 1 {
+        traits.push(format!("misses cost {}", weed.miss_cost));
+    }
+    text.0 = format!("{} weed: {}", weed.name, traits.join(", "));
+}
+
 fn regrow_rose(
     time: Res<Time>,
     mut commands: Commands,
@@ -1735,7 +2173,7 @@ fn update_healthbar_display(
     };
 
     if let Some(atlas) = sprite.texture_atlas.as_mut() {
-        let index = level.fail_count as usize + 1;
+        let index = level.run.fail_count() as usize + 1;
         if index <= 8 {
             atlas.index = index;
         } else {
@@ -1755,7 +2193,9 @@ fn update_timeboard(
         return;
     };
 
-    timeboard_text.0 = level.timer.remaining_secs().ceil().to_string();
+    timeboard_text.0 = (level.run.time_left_ms() as f32 / 1000.0)
+        .ceil()
+        .to_string();
 }
 
 fn game_over(
@@ -1858,16 +2298,16 @@ fn scene_transition(
         return;
     };
 
-    if *next_scene == AppState::GameOver
-        || (*next_scene != AppState::Game && *next_scene != AppState::LoadNextLevel)
-    {
+    // Split screen plays the game music like a level does
+    let playing = matches!(next_scene, AppState::Game | AppState::SplitScreen);
+    if *next_scene == AppState::GameOver || (!playing && *next_scene != AppState::LoadNextLevel) {
         if let Ok(entity) = game_music.single_mut() {
             commands
                 .entity(entity)
                 .insert(FadeOutMusic)
                 .remove::<MusicVolume>();
         }
-    } else if *next_scene == AppState::Game {
+    } else if playing {
         if let Ok(entity) = menu_music.single() {
             commands
                 .entity(entity)
@@ -2112,13 +2552,13 @@ fn setup_load_next_level(
     mut commands: Commands,
     hud: Res<Hud>,
     mut game_state: ResMut<NextState<GameState>>,
-    time_spent: Res<TimeSpent>,
     mut player_score: ResMut<PlayerScore>,
     current_level_id: Res<CurrentLevelId>,
     loaded_level: Res<LoadedLevel>,
     leaderboard_name: Res<LeaderboardName>,
     mut leaderboard_level: ResMut<LeaderboardLevelSelected>,
     mut advance: ResMut<Advance>,
+    mut hot_seat: ResMut<HotSeat>,
     mut music: Query<(Entity, &mut AudioSink, &MusicVolume)>,
 ) {
     if let Ok((entity, _, _)) = music.single() {
@@ -2126,31 +2566,23 @@ fn setup_load_next_level(
     }
 
     advance.0 = true;
-    let mut score = 0;
-    let time_spent_text = match time_spent.0.get(&current_level_id.0) {
-        Some(t) => {
-            score += (t * 1000.0).round() as u32;
-            format!("Time taken: {}s", (t * 1000.).round() / 1000.0)
-        }
-        None => String::new(),
+    let score = match &loaded_level.0 {
+        Some(level) => level.run.score(),
+        None => 0,
     };
-
-    let wrong_key_press_count_text = match &loaded_level.0 {
-        Some(level) => {
-            score += level.fail_count * 1000;
-            format!("Wrong keys pressed: {}", level.fail_count)
-        }
-        None => String::new(),
-    };
-
     player_score.0.insert(current_level_id.0, score);
+    if let Some(player) = hot_seat.current_mut() {
+        player.score.0.insert(current_level_id.0, score);
+    }
     leaderboard_level.0 = Some(current_level_id.0);
+    let player_name = match hot_seat.current() {
+        Some(player) => Some(player.name.clone()),
+        None => leaderboard_name.0.clone(),
+    };
 
     // Posting score to leaderboard
     commands.send_event(PostHighScore);
 
-    let score_text = format!("Your score is: {}", score);
-
     commands.entity(hud.0).with_children(|parent| {
         parent
             .spawn((
@@ -2195,27 +2627,28 @@ fn setup_load_next_level(
                 .observe(back_to_menu);
             });
 
-        parent
-            .spawn((
-                StateScoped(AppState::LoadNextLevel),
-                Node {
-                    position_type: PositionType::Absolute,
-                    display: Display::Flex,
-                    justify_self: JustifySelf::Center,
-                    flex_direction: FlexDirection::Column,
-                    width: Val::Px(100.0),
+        // A hot-seat turn is played once, the level moves on to the next player
+        if !hot_seat.is_active() {
+            parent
+                .spawn((
+                    StateScoped(AppState::LoadNextLevel),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        display: Display::Flex,
+                        justify_self: JustifySelf::Center,
+                        flex_direction: FlexDirection::Column,
+                        width: Val::Px(100.0),
 
-                    border: UiRect::all(Val::Px(2.0)),
-                    top: Val::Px(350.0),
-                    left: Val::Px(500.0),
-                    ..default()
-                },
-                BorderColor(LIGHT_COLOR),
-                BorderRadius::MAX,
-            ))
-            .with_children(|p| {
-                p.spawn(
-                    ((
+                        border: UiRect::all(Val::Px(2.0)),
+                        top: Val::Px(350.0),
+                        left: Val::Px(500.0),
+                        ..default()
+                    },
+                    BorderColor(LIGHT_COLOR),
+                    BorderRadius::MAX,
+                ))
+                .with_children(|p| {
+                    p.spawn((
                         Node {
                             width: Val::Percent(100.0),
                             ..default()
@@ -2231,12 +2664,12 @@ fn setup_load_next_level(
                                 .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
                                 .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
                             Pickable::IGNORE,
-                            TextSpan::new(format!("Retry")),
+                            TextSpan::new("Retry"),
                         )],
-                    )),
-                )
-                .observe(retry_level);
-            });
+                    ))
+                    .observe(retry_level);
+                });
+        }
 
         parent
             .spawn((
@@ -2297,7 +2730,7 @@ fn setup_load_next_level(
                 Pickable::IGNORE,
             ))
             .with_children(|p| {
-                let congrats = match &leaderboard_name.0 {
+                let congrats = match &player_name {
                     Some(name) => format!("Great job, {}!", name),
                     None => String::from("Great Job!"),
                 };
@@ -2310,7 +2743,7 @@ fn setup_load_next_level(
                 ));
                 p.spawn(spacer());
 
-                if leaderboard_name.0.is_none() {
+                if player_name.is_none() {
                     p.spawn((
                         TextColor(LIGHT_COLOR),
                         TextFont::from_font(BODY_FONT)
@@ -2324,26 +2757,13 @@ fn setup_load_next_level(
 
                 p.spawn(spacer());
                 p.spawn((
+                    Pickable::IGNORE,
+                    HighScoreSubmissionDisplay,
                     TextColor(LIGHT_COLOR),
                     TextFont::from_font(BODY_FONT)
-                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                    Text(time_spent_text),
-                ));
-                p.spawn(spacer());
-                p.spawn((
-                    TextColor(LIGHT_COLOR),
-                    TextFont::from_font(BODY_FONT)
-                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                    Text(wrong_key_press_count_text),
-                ));
-                p.spawn(spacer());
-                p.spawn((
-                    TextColor(LIGHT_COLOR),
-                    TextFont::from_font(BODY_FONT)
-                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                    Text(score_text),
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 35.),
+                    Text("".into()),
                 ));
-                p.spawn(spacer());
                 p.spawn((
                     Pickable::IGNORE,
                     TextColor(LIGHT_COLOR),
@@ -2367,7 +2787,12 @@ fn setup_game_over(
     mut commands: Commands,
     hud: Res<Hud>,
     mut game_state: ResMut<NextState<GameState>>,
+    hot_seat: Res<HotSeat>,
 ) {
+    let retry_text = match hot_seat.is_active() {
+        true => "Next",
+        false => "Retry",
+    };
     game_state.set(GameState::NotRunning);
     commands.entity(hud.0).with_children(|parent| {
         parent
@@ -2449,7 +2874,7 @@ fn setup_game_over(
                                 .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
                                 .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
                             Pickable::IGNORE,
-                            TextSpan::new(format!("Retry")),
+                            TextSpan::new(retry_text),
                         )],
                     )),
                 )
@@ -2493,8 +2918,14 @@ fn load_next_level(
     mut advance: ResMut<Advance>,
     mut current_level_id: ResMut<CurrentLevelId>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut hot_seat: ResMut<HotSeat>,
     mut commands: Commands,
 ) {
+    if let Some(next_scene) = hot_seat.pass_turn(&mut advance) {
+        game_state.set(GameState::NotRunning);
+        commands.send_event(SceneChange(next_scene));
+        return;
+    }
     // for event in events.read() {
     // if event.state != ButtonState::Released {
     if advance.0 {
@@ -2511,8 +2942,14 @@ fn load_next_level_on_spacebar(
     mut advance: ResMut<Advance>,
     mut current_level_id: ResMut<CurrentLevelId>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut hot_seat: ResMut<HotSeat>,
     mut commands: Commands,
 ) {
+    if let Some(next_scene) = hot_seat.pass_turn(&mut advance) {
+        game_state.set(GameState::NotRunning);
+        commands.send_event(SceneChange(next_scene));
+        return;
+    }
     if advance.0 {
         current_level_id.0 += 1;
         advance.0 = false;
@@ -2521,26 +2958,31 @@ fn load_next_level_on_spacebar(
     commands.send_event(SceneChange(AppState::Game));
 }
 
+// In hot-seat there are no retries, a lost turn goes to the next player
 fn retry_level(
     _: Trigger<Pointer<Click>>,
     mut advance: ResMut<Advance>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut hot_seat: ResMut<HotSeat>,
     mut commands: Commands,
 ) {
     advance.0 = false;
+    let next_scene = hot_seat.pass_turn(&mut advance).unwrap_or(AppState::Game);
     game_state.set(GameState::NotRunning);
-    commands.send_event(SceneChange(AppState::Game));
+    commands.send_event(SceneChange(next_scene));
 }
 
 fn restart_game_at_menu(
     // mut current_level_id: ResMut<CurrentLevelId>,
     mut advance: ResMut<Advance>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut hot_seat: ResMut<HotSeat>,
     mut commands: Commands,
 ) {
     advance.0 = false;
+    let next_scene = hot_seat.pass_turn(&mut advance).unwrap_or(AppState::Game);
     game_state.set(GameState::NotRunning);
-    commands.send_event(SceneChange(AppState::Game));
+    commands.send_event(SceneChange(next_scene));
 }
 
 #[derive(Resource, Default, Eq, PartialEq)]
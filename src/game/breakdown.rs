@@ -0,0 +1,225 @@
+use super::ghost::{PersonalBests, player_name, record_personal_best};
+use super::{CurrentLevelId, HotSeat, LoadedLevel};
+use crate::app::{AppState, LIGHT_COLOR, RESOLUTION_HEIGHT};
+use crate::menu::LeaderboardName;
+use crate::util::handles::BODY_FONT;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+
+// Seconds between rows appearing, and how long each takes to count up
+const ROW_DELAY: f32 = 0.35;
+const COUNT_UP: f32 = 0.5;
+
+// The level complete screen's score, tallied up a row at a time
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Tally::default())
+        .add_systems(
+            OnEnter(AppState::LoadNextLevel),
+            setup_breakdown.before(record_personal_best),
+        )
+        .add_systems(
+            Update,
+            tally_breakdown.run_if(in_state(AppState::LoadNextLevel)),
+        );
+}
+
+#[derive(Resource, Default)]
+pub struct Tally {
+    elapsed: f32,
+}
+
+#[derive(Clone, Copy)]
+enum TallyFormat {
+    Seconds,
+    AddSeconds,
+    SubtractSeconds,
+    Count,
+    Percent,
+    Stars,
+}
+
+impl TallyFormat {
+    fn format(self, value: f32) -> String {
+        match self {
+            Self::Seconds => format!("{:.2}s", value / 1000.0),
+            Self::AddSeconds => format!("+{:.2}s", value / 1000.0),
+            Self::SubtractSeconds => format!("-{:.2}s", value / 1000.0),
+            Self::Count => format!("{}", value.round()),
+            Self::Percent => format!("{}%", value.round()),
+            Self::Stars => format!("{}/3", value.round()),
+        }
+    }
+}
+
+// The value column of one row, counting up to its target once the row shows
+#[derive(Component)]
+struct TallyRow {
+    index: usize,
+    target: f32,
+    format: TallyFormat,
+}
+
+// Hidden until its turn, the record line comes after every row has counted up
+#[derive(Component)]
+struct TallyReveal {
+    index: usize,
+}
+
+fn row_progress(elapsed: f32, index: usize) -> f32 {
+    ((elapsed - index as f32 * ROW_DELAY) / COUNT_UP).clamp(0.0, 1.0)
+}
+
+fn setup_breakdown(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    loaded_level: Res<LoadedLevel>,
+    current_level_id: Res<CurrentLevelId>,
+    personal_bests: Res<PersonalBests>,
+    leaderboard_name: Res<LeaderboardName>,
+    hot_seat: Res<HotSeat>,
+    mut tally: ResMut<Tally>,
+) {
+    tally.elapsed = 0.0;
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+    let run = &level.run;
+    let scoring = &run.rules().scoring;
+
+    let correct = run.prompt_times().len() as f32;
+    let pressed = correct + run.fail_count() as f32;
+    let accuracy = match pressed > 0.0 {
+        true => correct / pressed * 100.0,
+        false => 0.0,
+    };
+    let minutes = run.elapsed_ms() as f32 / 60_000.0;
+    let letters_per_minute = match minutes > 0.0 {
+        true => correct / minutes,
+        false => 0.0,
+    };
+
+    let rows = [
+        (
+            "Base time".to_string(),
+            scoring.weighted_time(run.elapsed_ms()) as f32,
+            TallyFormat::Seconds,
+        ),
+        (
+            format!("Wrong keys ({})", run.fail_count()),
+            scoring.penalty(run.fail_count()) as f32,
+            TallyFormat::AddSeconds,
+        ),
+        (
+            "Streak bonus".to_string(),
+            run.streak_bonus_ms() as f32,
+            TallyFormat::SubtractSeconds,
+        ),
+        (
+            "Best streak".to_string(),
+            run.best_streak() as f32,
+            TallyFormat::Count,
+        ),
+        ("Accuracy".to_string(), accuracy, TallyFormat::Percent),
+        (
+            "Letters per minute".to_string(),
+            letters_per_minute,
+            TallyFormat::Count,
+        ),
+        ("Score".to_string(), run.score() as f32, TallyFormat::Count),
+        ("Stars".to_string(), run.stars() as f32, TallyFormat::Stars),
+    ];
+
+    // Compared before this run is recorded as the new best
+    let score = run.score();
+    let player = player_name(&hot_seat, &leaderboard_name);
+    let previous = personal_bests.get(&player, current_level_id.0);
+    let footer = match previous {
+        Some(best) if scoring.is_better(score, best.score) => {
+            format!("Personal best: {}\nNew record!", best.score)
+        }
+        Some(best) => format!("Personal best: {}", best.score),
+        None => "First clear, new record!".to_string(),
+    };
+
+    let footer_index = rows.len() + 1;
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::LoadNextLevel),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Px(260.0),
+                    top: Val::Px(110.0),
+                    left: Val::Px(340.0),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|p| {
+                for (index, (label, target, format)) in rows.into_iter().enumerate() {
+                    p.spawn((
+                        Node {
+                            display: Display::Flex,
+                            justify_content: JustifyContent::SpaceBetween,
+                            width: Val::Percent(100.0),
+                            ..default()
+                        },
+                        Visibility::Hidden,
+                        TallyReveal { index },
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            TextColor(LIGHT_COLOR),
+                            TextFont::from_font(BODY_FONT)
+                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                            Text(label),
+                        ));
+                        row.spawn((
+                            TallyRow {
+                                index,
+                                target,
+                                format,
+                            },
+                            TextColor(LIGHT_COLOR),
+                            TextFont::from_font(BODY_FONT)
+                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                            Text(format.format(0.0)),
+                        ));
+                    });
+                }
+
+                p.spawn((
+                    TallyReveal {
+                        index: footer_index,
+                    },
+                    Visibility::Hidden,
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                    Text(format!("\n{}", footer)),
+                ));
+            });
+    });
+}
+
+fn tally_breakdown(
+    time: Res<Time>,
+    mut tally: ResMut<Tally>,
+    mut rows: Query<(&TallyRow, &mut Text)>,
+    mut reveals: Query<(&TallyReveal, &mut Visibility)>,
+) {
+    tally.elapsed += time.delta_secs();
+
+    for (reveal, mut visibility) in &mut reveals {
+        if tally.elapsed >= reveal.index as f32 * ROW_DELAY {
+            *visibility = Visibility::Inherited;
+        }
+    }
+    for (row, mut text) in &mut rows {
+        let value = row.target * row_progress(tally.elapsed, row.index);
+        text.0 = row.format.format(value);
+    }
+}
@@ -0,0 +1,171 @@
+use super::touch_keyboard::{KEY_SIZE, key_node, spawn_key, type_keys};
+use super::{ActiveKey, KeyMap, KeyPosition};
+use crate::app::{AppState, DARK_COLOR, RESOLUTION_HEIGHT};
+use crate::assets::custom::ImageAssets;
+use crate::controls::{Action, Bindings};
+use crate::util::handles::BODY_FONT;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_aspect_ratio_mask::Hud;
+use weed_core::prompt_keys;
+
+// A faded letterbox sits on every key, and the prompt is played by clicking its box. A
+// click sends the same KeyboardInput as typing the prompt would, so hits and misses are
+// scored the same way.
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(ClickMode::default()).add_systems(
+        Update,
+        show_click_keys.run_if(
+            in_state(AppState::Game)
+                .or(in_state(AppState::GameOver))
+                .or(in_state(AppState::LoadNextLevel)),
+        ),
+    );
+}
+
+// Chosen from the menu, it lasts until a game is started the other way
+#[derive(Resource, Default)]
+pub struct ClickMode(pub bool);
+
+#[derive(Component)]
+struct ClickKeysRoot;
+
+#[derive(Component)]
+struct ClickKey(char);
+
+fn show_click_keys(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    click_mode: Res<ClickMode>,
+    state: Res<State<AppState>>,
+    image_assets: Res<ImageAssets>,
+    key_position: Res<KeyPosition>,
+    bindings: Res<Bindings>,
+    shown: Query<(), With<ClickKeysRoot>>,
+) {
+    if !click_mode.0 || !shown.is_empty() {
+        return;
+    }
+
+    let state = state.get().clone();
+    let playing = state == AppState::Game;
+    let y_space = -RESOLUTION_HEIGHT / 2.0 + KEY_SIZE / 2.0 + 10.0;
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(state),
+                ClickKeysRoot,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|p| {
+                if playing {
+                    for (c, position) in &key_position.0 {
+                        spawn_click_key(p, &image_assets, *c, position.truncate());
+                    }
+                }
+                spawn_key(
+                    p,
+                    bindings.key(Action::Start),
+                    "start",
+                    key_node(Vec2::new(0.0, y_space), KEY_SIZE * 5.0),
+                );
+            });
+    });
+}
+
+// Faded so the prompt's own letterbox shows through when it lands on the key
+fn spawn_click_key(
+    parent: &mut ChildSpawnerCommands,
+    image_assets: &ImageAssets,
+    key: char,
+    center: Vec2,
+) {
+    parent
+        .spawn((
+            ClickKey(key),
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                padding: UiRect::ZERO,
+                border: UiRect::ZERO,
+                ..key_node(center, KEY_SIZE)
+            },
+            ImageNode {
+                image: image_assets.letterbox.clone(),
+                color: Color::WHITE.with_alpha(0.35),
+                texture_atlas: Some(TextureAtlas {
+                    layout: image_assets.letterbox_layout.clone(),
+                    index: 4,
+                }),
+                ..default()
+            },
+        ))
+        .with_children(|p| match key {
+            'a'..='z' => {
+                p.spawn((
+                    Pickable::IGNORE,
+                    Node {
+                        width: Val::Px(20.0),
+                        height: Val::Px(20.0),
+                        ..default()
+                    },
+                    ImageNode {
+                        image: image_assets.letters.clone(),
+                        color: Color::WHITE.with_alpha(0.5),
+                        texture_atlas: Some(TextureAtlas {
+                            layout: image_assets.letters_layout.clone(),
+                            index: key as usize - 'a' as usize,
+                        }),
+                        ..default()
+                    },
+                ));
+            }
+            _ => {
+                p.spawn((
+                    Pickable::IGNORE,
+                    TextColor(DARK_COLOR.with_alpha(0.5)),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                    Text(key.to_string()),
+                ));
+            }
+        })
+        .observe(click_key);
+}
+
+// Clicking any key of the prompt plays the whole prompt, shift and chord keys
+// included. Any other key is a miss, the same as typing it.
+fn click_key(
+    trigger: Trigger<Pointer<Pressed>>,
+    click_keys: Query<&ClickKey>,
+    active_key: Res<ActiveKey>,
+    key_map: Res<KeyMap>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut keyboard_events: EventWriter<KeyboardInput>,
+) {
+    let (Ok(click_key), Ok(window)) = (click_keys.get(trigger.target), window.single()) else {
+        return;
+    };
+
+    let prompt = active_key.0.as_deref().map(prompt_keys).unwrap_or_default();
+    let on_prompt = prompt
+        .iter()
+        .any(|c| key_map.base(*c).unwrap_or(*c) == click_key.0);
+    let pressed = match on_prompt {
+        true => prompt,
+        false => vec![click_key.0],
+    }
+    .into_iter()
+    .filter_map(|c| key_map.0.get(&c).copied())
+    .collect::<Vec<_>>();
+
+    type_keys(&pressed, &mut keys, &mut keyboard_events, window);
+}
@@ -0,0 +1,264 @@
+use super::touch_keyboard::{keyboard_input, type_keys};
+use super::{ActiveKey, KeyMap, Letterbox, keypress_events};
+use crate::app::{AppState, LIGHT_COLOR};
+use crate::controls::{Action, Bindings};
+use crate::util::handles::BODY_FONT;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::f32::consts::FRAC_PI_4;
+use weed_core::prompt_keys;
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+// Letters then digits, four to a direction in the order of BUTTONS
+const KEYS: &str = "abcdefghijklmnopqrstuvwxyz1234567890";
+// The rest of the keyboard, on the same combos with SYMBOLS held
+const SYMBOL_KEYS: &str = "-=[]\\;',./`";
+
+// Held along with a combo, for the symbol keys and for Shift
+const SYMBOLS: (GamepadButton, &str) = (GamepadButton::LeftTrigger, "LB");
+const SHIFT: (GamepadButton, &str) = (GamepadButton::RightTrigger, "RB");
+
+const BUTTONS: [(GamepadButton, &str); 4] = [
+    (GamepadButton::South, "A"),
+    (GamepadButton::East, "B"),
+    (GamepadButton::West, "X"),
+    (GamepadButton::North, "Y"),
+];
+
+// Every key is a face button, pressed with the stick or D-pad held one way. Holding a
+// bumper switches to the symbol keys or holds Shift. The combination is written under
+// each letterbox, and pressing it sends the same KeyboardInput as the key would.
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GamepadPlay::default())
+        .add_systems(Update, detect_gamepad)
+        .add_systems(
+            Update,
+            // Before the key events are read, while Shift is still held for them
+            (
+                gamepad_keypresses.before(keypress_events),
+                label_letterboxes,
+            )
+                .run_if(
+                    in_state(AppState::Game)
+                        .or(in_state(AppState::GameOver))
+                        .or(in_state(AppState::LoadNextLevel)),
+                ),
+        );
+}
+
+#[derive(Resource, Default)]
+pub struct GamepadPlay {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Neutral,
+    Up,
+    Right,
+    Down,
+    Left,
+    UpRight,
+    DownRight,
+    DownLeft,
+    UpLeft,
+}
+
+impl Direction {
+    const ALL: [Direction; 9] = [
+        Self::Neutral,
+        Self::Up,
+        Self::Right,
+        Self::Down,
+        Self::Left,
+        Self::UpRight,
+        Self::DownRight,
+        Self::DownLeft,
+        Self::UpLeft,
+    ];
+
+    fn of(gamepad: &Gamepad) -> Self {
+        let mut direction = gamepad.left_stick();
+        if direction.length() < STICK_THRESHOLD {
+            direction = gamepad.dpad();
+        }
+        if direction.length() < STICK_THRESHOLD {
+            return Self::Neutral;
+        }
+        // Counter-clockwise eighths starting from right
+        match (direction.y.atan2(direction.x) / FRAC_PI_4).round() as i32 {
+            -4 | 4 => Self::Left,
+            -3 => Self::DownLeft,
+            -2 => Self::Down,
+            -1 => Self::DownRight,
+            0 => Self::Right,
+            1 => Self::UpRight,
+            2 => Self::Up,
+            _ => Self::UpLeft,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Neutral => "",
+            Self::Up => "Up",
+            Self::Right => "Right",
+            Self::Down => "Down",
+            Self::Left => "Left",
+            Self::UpRight => "Up-Right",
+            Self::DownRight => "Down-Right",
+            Self::DownLeft => "Down-Left",
+            Self::UpLeft => "Up-Left",
+        }
+    }
+}
+
+// Buttons that type in gamepad play, the D-pad included as it picks the key
+pub fn is_play_button(button: GamepadButton) -> bool {
+    BUTTONS.iter().any(|(b, _)| *b == button)
+        || button == SYMBOLS.0
+        || button == SHIFT.0
+        || matches!(
+            button,
+            GamepadButton::DPadUp
+                | GamepadButton::DPadDown
+                | GamepadButton::DPadLeft
+                | GamepadButton::DPadRight
+        )
+}
+
+fn page(symbols: bool) -> &'static str {
+    match symbols {
+        true => SYMBOL_KEYS,
+        false => KEYS,
+    }
+}
+
+// The unshifted key a combo types
+fn key_for(direction: Direction, button: GamepadButton, symbols: bool) -> Option<char> {
+    let row = Direction::ALL.iter().position(|d| *d == direction)?;
+    let column = BUTTONS.iter().position(|(b, _)| *b == button)?;
+    page(symbols).chars().nth(row * BUTTONS.len() + column)
+}
+
+// e.g. "Up+X" for 'k', "RB+Up+X" for 'K' and "LB+B" for '='
+fn combo_label(key_map: &KeyMap, key: char) -> Option<String> {
+    let base = key_map.base(key)?;
+    let (symbols, index) = [false, true]
+        .into_iter()
+        .find_map(|symbols| Some((symbols, page(symbols).chars().position(|c| c == base)?)))?;
+    let direction = Direction::ALL[index / BUTTONS.len()];
+    let (_, button) = BUTTONS[index % BUTTONS.len()];
+
+    let mut combo = vec![];
+    if base != key {
+        combo.push(SHIFT.1);
+    }
+    if symbols {
+        combo.push(SYMBOLS.1);
+    }
+    if direction != Direction::Neutral {
+        combo.push(direction.label());
+    }
+    combo.push(button);
+    Some(combo.join("+"))
+}
+
+// The first button press switches gamepad play on for the rest of the session
+fn detect_gamepad(gamepads: Query<&Gamepad>, mut gamepad_play: ResMut<GamepadPlay>) {
+    if !gamepad_play.enabled
+        && gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        gamepad_play.enabled = true;
+    }
+}
+
+// The letters are only for playing. Any key of a chord prompt plays the whole chord, its
+// keys couldn't be pressed together otherwise.
+fn gamepad_keypresses(
+    gamepads: Query<&Gamepad>,
+    state: Res<State<AppState>>,
+    key_map: Res<KeyMap>,
+    active_key: Res<ActiveKey>,
+    bindings: Res<Bindings>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut keyboard_events: EventWriter<KeyboardInput>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+
+    for gamepad in &gamepads {
+        // Start is bound like the other actions, but the first prompt only shows on a
+        // key event. Letting go of the Start key gives one without pressing it twice.
+        if *state.get() == AppState::Game && bindings.button_just_pressed(Action::Start, gamepad) {
+            keyboard_events.write(keyboard_input(
+                bindings.key(Action::Start),
+                ButtonState::Released,
+                window,
+            ));
+        }
+        let direction = Direction::of(gamepad);
+        let symbols = gamepad.pressed(SYMBOLS.0);
+        let shift = gamepad.pressed(SHIFT.0);
+        let chord = active_key
+            .0
+            .as_deref()
+            .map(prompt_keys)
+            .filter(|chord| chord.len() > 1)
+            .unwrap_or_default();
+        for (button, _) in BUTTONS {
+            if *state.get() != AppState::Game || !gamepad.just_pressed(button) {
+                continue;
+            }
+            let Some(&(key_code, _)) =
+                key_for(direction, button, symbols).and_then(|key| key_map.0.get(&key))
+            else {
+                continue;
+            };
+            let pressed = match key_map.key_for(key_code, shift) {
+                Some(key) if chord.contains(&key) => chord
+                    .iter()
+                    .filter_map(|key| key_map.0.get(key).copied())
+                    .collect(),
+                _ => vec![(key_code, shift)],
+            };
+            type_keys(&pressed, &mut keys, &mut keyboard_events, window);
+        }
+    }
+}
+
+// A chord is labelled with its first key, which plays all of it
+fn label_letterboxes(
+    mut commands: Commands,
+    gamepad_play: Res<GamepadPlay>,
+    key_map: Res<KeyMap>,
+    letterboxes: Query<(Entity, &Letterbox), Added<Letterbox>>,
+) {
+    if !gamepad_play.enabled {
+        return;
+    }
+
+    for (entity, letterbox) in &letterboxes {
+        let Some(label) = prompt_keys(&letterbox.prompt)
+            .first()
+            .and_then(|key| combo_label(&key_map, *key))
+        else {
+            continue;
+        };
+        commands.entity(entity).with_children(|p| {
+            p.spawn((
+                Text2d::new(label),
+                TextFont::from_font(BODY_FONT).with_font_size(12.0),
+                TextColor(LIGHT_COLOR),
+                Transform::from_translation(Vec3::new(0.0, -38.0, 1.0)),
+            ));
+        });
+    }
+}
@@ -0,0 +1,421 @@
+use super::versus::Versus;
+use super::{
+    CurrentLevelId, GameState, HotSeat, Level, LoadedLevel, RoseGrows, RunRecord,
+    setup as setup_game,
+};
+use crate::app::{AppState, LIGHT_COLOR, RESOLUTION_HEIGHT};
+use crate::assets::custom::ImageAssets;
+use crate::controls::{Action, Bindings, action_just_pressed, key_label, read_saved, write_saved};
+use crate::leaderboard::LEADERBOARD_URL;
+use crate::menu::LeaderboardName;
+use crate::util::handles::BODY_FONT;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use bevy_http_client::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use weed_core::{InputLog, Outcome, Run};
+
+// What the personal bests are saved as between sessions
+const PERSONAL_BESTS: &str = "personal-bests";
+
+// A faint second rose that grows with a recorded run of the same level and seed, so
+// the player can see whether they're ahead of or behind it
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GhostSource::default())
+        .insert_resource(PersonalBests::load())
+        .insert_resource(LeaderboardGhost::default())
+        .insert_resource(ActiveGhost::default())
+        .add_systems(
+            OnEnter(AppState::Game),
+            (fetch_leaderboard_ghost, setup_ghost).after(setup_game),
+        )
+        .add_systems(OnEnter(AppState::LoadNextLevel), record_personal_best)
+        .add_systems(Update, handle_ghost_response)
+        .add_systems(
+            Update,
+            (
+                cycle_ghost_source.run_if(action_just_pressed(Action::Ghost)),
+                choose_ghost,
+            )
+                .chain()
+                .run_if(in_state(AppState::Game).and(in_state(GameState::NotRunning))),
+        )
+        .add_systems(
+            Update,
+            advance_ghost.run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
+        )
+        .add_systems(
+            Update,
+            (update_ghost_rose, update_ghost_display)
+                .after(advance_ghost)
+                .run_if(in_state(AppState::Game)),
+        );
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+// Off until the player picks one. Racing reseeds the level to the ghost's seed, so the
+// prompts are the same every time.
+pub enum GhostSource {
+    PersonalBest,
+    Leaderboard,
+    #[default]
+    Off,
+}
+
+impl GhostSource {
+    fn next(self) -> Self {
+        match self {
+            Self::PersonalBest => Self::Leaderboard,
+            Self::Leaderboard => Self::Off,
+            Self::Off => Self::PersonalBest,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::PersonalBest => "Personal best",
+            Self::Leaderboard => "Leaderboard best",
+            Self::Off => "Off",
+        }
+    }
+}
+
+// A completed run, in the shape the lambda's GET /replays/{level} returns it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GhostRun {
+    pub name: String,
+    pub level: usize,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub inputs: InputLog,
+    pub score: u32,
+}
+
+// Each player's best completed run of each level, kept between sessions. Hot-seat
+// players each have their own.
+#[derive(Resource, Default)]
+pub struct PersonalBests(pub HashMap<String, HashMap<usize, GhostRun>>);
+
+impl PersonalBests {
+    pub fn get(&self, player: &str, level: usize) -> Option<&GhostRun> {
+        self.0.get(player)?.get(&level)
+    }
+
+    fn load() -> Self {
+        let saved = read_saved(PERSONAL_BESTS).and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| warn!(?e, "Ignoring saved personal bests"))
+                .ok()
+        });
+        Self(saved.unwrap_or_default())
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.0) {
+            Ok(json) => write_saved(PERSONAL_BESTS, &json),
+            Err(e) => error!(?e),
+        }
+    }
+}
+
+// The all time leader's run of the current level, when the leaderboard has one
+// Whose bests a run counts towards: the hot-seat player whose turn it is, or whoever is
+// playing under the leaderboard name
+pub fn player_name(hot_seat: &HotSeat, leaderboard_name: &LeaderboardName) -> String {
+    match hot_seat.current() {
+        Some(player) => player.name.clone(),
+        None => leaderboard_name.0.clone().unwrap_or_else(|| "You".into()),
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LeaderboardGhost(pub Option<GhostRun>);
+
+// The recorded run being raced, played back through the same rules as the player
+pub struct Ghost {
+    name: String,
+    score: u32,
+    run: Run,
+    inputs: InputLog,
+    next_input: usize,
+}
+
+impl Ghost {
+    fn new(ghost_run: &GhostRun, level: &Level) -> Self {
+        Self {
+            name: ghost_run.name.clone(),
+            score: ghost_run.score,
+            run: Run::new(level.run.rules().clone(), ghost_run.seed),
+            inputs: ghost_run.inputs.clone(),
+            next_input: 0,
+        }
+    }
+
+    // Presses every recorded key that was pressed by `now`
+    fn advance(&mut self, now: u32) {
+        let inputs = self.inputs.keys.chars().zip(&self.inputs.times);
+        for (key, &at) in inputs.skip(self.next_input) {
+            if at > now {
+                break;
+            }
+            self.run.press(at, key);
+            self.next_input += 1;
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ActiveGhost(pub Option<Ghost>);
+
+#[derive(Component)]
+pub struct GhostRose;
+
+#[derive(Component)]
+pub struct GhostDisplay;
+
+fn fetch_leaderboard_ghost(
+    run_record: Res<RunRecord>,
+    mut leaderboard_ghost: ResMut<LeaderboardGhost>,
+    mut ev_request: EventWriter<HttpRequest>,
+) {
+    leaderboard_ghost.0 = None;
+    let url = format!("{}/replays/{}", LEADERBOARD_URL, run_record.level);
+    if let Ok(request) = HttpClient::new()
+        .get(url)
+        .try_build()
+        .map_err(|e| error!(?e))
+    {
+        ev_request.write(request);
+    }
+}
+
+fn handle_ghost_response(
+    mut ev_resp: EventReader<HttpResponse>,
+    run_record: Res<RunRecord>,
+    mut leaderboard_ghost: ResMut<LeaderboardGhost>,
+) {
+    for response in ev_resp.read() {
+        if !response.ok {
+            continue;
+        }
+        // Other leaderboard responses won't parse as a run
+        let ghost_run = response.json::<GhostRun>().ok();
+        if let Some(ghost_run) = ghost_run.filter(|run| run.level == run_record.level) {
+            leaderboard_ghost.0 = Some(ghost_run);
+        }
+    }
+}
+
+fn setup_ghost(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    image_assets: Res<ImageAssets>,
+    mut active_ghost: ResMut<ActiveGhost>,
+) {
+    active_ghost.0 = None;
+
+    commands.spawn((
+        StateScoped(AppState::Game),
+        GhostRose,
+        Visibility::Hidden,
+        Transform::from_translation(Vec3::new(-90.0, -50.0, -11.0)).with_scale(Vec3::splat(0.6)),
+        Sprite {
+            image: image_assets.rose.clone(),
+            color: Color::WHITE.with_alpha(0.35),
+            texture_atlas: Some(TextureAtlas {
+                layout: image_assets.rose_layout.clone(),
+                index: 0,
+            }),
+            ..default()
+        },
+    ));
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::Game),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(40.0),
+                    align_items: AlignItems::End,
+                    padding: UiRect::all(Val::Px(15.)),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    GhostDisplay,
+                    TextColor(LIGHT_COLOR.with_alpha(0.6)),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 35.),
+                    Text("".into()),
+                ));
+            });
+    });
+}
+
+fn cycle_ghost_source(mut ghost_source: ResMut<GhostSource>) {
+    *ghost_source = ghost_source.next();
+}
+
+// Picks the run to race before the level starts. The level is reseeded to the ghost's
+// seed so both get the same prompts.
+fn choose_ghost(
+    ghost_source: Res<GhostSource>,
+    personal_bests: Res<PersonalBests>,
+    leaderboard_ghost: Res<LeaderboardGhost>,
+    current_level_id: Res<CurrentLevelId>,
+    leaderboard_name: Res<LeaderboardName>,
+    hot_seat: Res<HotSeat>,
+    mut active_ghost: ResMut<ActiveGhost>,
+    mut run_record: ResMut<RunRecord>,
+    mut loaded_level: ResMut<LoadedLevel>,
+    versus: Res<Versus>,
+) {
+    // An online race has its own seed
+    if versus.is_racing() {
+        active_ghost.0 = None;
+        return;
+    }
+    // Only while nothing has been played on the loaded level
+    if run_record.now_ms() > 0 || !run_record.inputs.is_empty() {
+        return;
+    }
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+
+    let ghost_run = match *ghost_source {
+        GhostSource::PersonalBest => personal_bests.get(
+            &player_name(&hot_seat, &leaderboard_name),
+            current_level_id.0,
+        ),
+        GhostSource::Leaderboard => leaderboard_ghost.0.as_ref(),
+        GhostSource::Off => None,
+    };
+    let Some(ghost_run) = ghost_run else {
+        active_ghost.0 = None;
+        return;
+    };
+
+    let unchanged = active_ghost.0.as_ref().is_some_and(|ghost| {
+        ghost.name == ghost_run.name
+            && ghost.score == ghost_run.score
+            && run_record.seed == ghost_run.seed
+    });
+    if unchanged {
+        return;
+    }
+
+    let ghost = Ghost::new(ghost_run, level);
+    let rules = level.run.rules().clone();
+    run_record.seed = ghost_run.seed;
+    loaded_level.0 = Some(Level::new(rules, ghost_run.seed, run_record.level));
+    active_ghost.0 = Some(ghost);
+}
+
+fn advance_ghost(run_record: Res<RunRecord>, mut active_ghost: ResMut<ActiveGhost>) {
+    if let Some(ghost) = active_ghost.0.as_mut() {
+        ghost.advance(run_record.now_ms());
+    }
+}
+
+pub(super) fn record_personal_best(
+    run_record: Res<RunRecord>,
+    loaded_level: Res<LoadedLevel>,
+    leaderboard_name: Res<LeaderboardName>,
+    hot_seat: Res<HotSeat>,
+    mut personal_bests: ResMut<PersonalBests>,
+) {
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+    if level.run.outcome() != Outcome::Complete {
+        return;
+    }
+
+    let score = level.run.score();
+    let name = player_name(&hot_seat, &leaderboard_name);
+    let previous = personal_bests.get(&name, run_record.level);
+    let scoring = &level.run.rules().scoring;
+    if previous.is_some_and(|best| !scoring.is_better(score, best.score)) {
+        return;
+    }
+    personal_bests.0.entry(name.clone()).or_default().insert(
+        run_record.level,
+        GhostRun {
+            name,
+            level: run_record.level,
+            seed: run_record.seed,
+            inputs: run_record.inputs.clone(),
+            score,
+        },
+    );
+    personal_bests.save();
+}
+
+// Same growth as the player's rose, driven by the ghost's weeds left
+fn update_ghost_rose(
+    active_ghost: Res<ActiveGhost>,
+    mut rose: Query<(&mut Sprite, &mut Visibility), (With<GhostRose>, Without<RoseGrows>)>,
+) {
+    let Ok((mut sprite, mut visibility)) = rose.single_mut() else {
+        return;
+    };
+    let Some(ghost) = active_ghost.0.as_ref() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    if let Some(atlas) = sprite.texture_atlas.as_mut() {
+        atlas.index = match ghost.run.outcome() {
+            Outcome::Complete => 9,
+            _ => 9 - ghost.run.weeds_left().min(9) as usize,
+        };
+    }
+}
+
+fn update_ghost_display(
+    ghost_source: Res<GhostSource>,
+    active_ghost: Res<ActiveGhost>,
+    loaded_level: Res<LoadedLevel>,
+    game_state: Res<State<GameState>>,
+    bindings: Res<Bindings>,
+    mut display: Query<&mut Text, With<GhostDisplay>>,
+) {
+    let Ok(mut text) = display.single_mut() else {
+        return;
+    };
+
+    if *game_state.get() == GameState::NotRunning {
+        let key = key_label(bindings.key(Action::Ghost));
+        text.0 = match (*ghost_source, active_ghost.0.as_ref()) {
+            (GhostSource::Off, _) => format!("Ghost: Off ({})", key),
+            (source, Some(ghost)) => format!("Ghost: {} {} ({})", source.label(), ghost.name, key),
+            (source, None) => format!("Ghost: {}, none yet ({})", source.label(), key),
+        };
+        return;
+    }
+
+    let (Some(ghost), Some(level)) = (active_ghost.0.as_ref(), loaded_level.0.as_ref()) else {
+        text.0 = String::new();
+        return;
+    };
+    let ghost_left = ghost.run.weeds_left();
+    let player_left = level.run.weeds_left();
+    let standing = if ghost.run.outcome() == Outcome::Complete {
+        "finished"
+    } else if player_left < ghost_left {
+        "ahead"
+    } else if player_left > ghost_left {
+        "behind"
+    } else {
+        "even"
+    };
+    text.0 = format!("Ghost: {} left, {}", ghost_left, standing);
+}
@@ -0,0 +1,579 @@
+use super::{
+    Advance, CurrentLevelId, GameState, PlayerScore, SceneChange, setup as setup_game, spacer,
+};
+use crate::app::{AppState, DARK_COLOR, LIGHT_COLOR, RESOLUTION_HEIGHT};
+use crate::assets::lexi::levels::LevelLex;
+use crate::controls::{Action, action_just_pressed};
+use crate::leaderboard::level_scoring;
+use crate::util::handles::BODY_FONT;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use bevy_simple_text_input::{
+    TextInput, TextInputInactive, TextInputPlaceholder, TextInputSettings, TextInputTextColor,
+    TextInputTextFont, TextInputValue,
+};
+use weed_core::NameRules;
+
+pub const HOT_SEAT_MIN_PLAYERS: usize = 2;
+pub const HOT_SEAT_MAX_PLAYERS: usize = 4;
+
+// Named players taking turns at each level on one machine, for team events
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(HotSeat::default())
+        .add_systems(OnEnter(AppState::Menu), end_hot_seat)
+        .add_systems(OnEnter(AppState::HotSeatSetup), setup_hot_seat)
+        .add_systems(
+            Update,
+            (
+                cycle_player_input_focus.run_if(input_just_pressed(KeyCode::Tab)),
+                update_hot_seat_feedback.run_if(on_event::<KeyboardInput>),
+                start_hot_seat_on_enter.run_if(action_just_pressed(Action::Confirm)),
+                toggle_post_scores_label,
+            )
+                .run_if(in_state(AppState::HotSeatSetup)),
+        )
+        .add_systems(OnEnter(AppState::Game), show_turn.after(setup_game))
+        .add_systems(OnEnter(AppState::HotSeatResults), setup_hot_seat_results)
+        .add_systems(
+            Update,
+            continue_hot_seat_on_spacebar
+                .run_if(action_just_pressed(Action::Start).and(in_state(AppState::HotSeatResults))),
+        );
+}
+
+pub struct HotSeatPlayer {
+    pub name: String,
+    pub score: PlayerScore,
+}
+
+impl HotSeatPlayer {
+    fn levels_completed(&self) -> usize {
+        self.score.0.len()
+    }
+
+    fn total(&self) -> u32 {
+        self.score.0.values().sum()
+    }
+}
+
+// Every player plays the current level in turn, then the standings are shown before
+// the next level. No players means hot-seat isn't being played.
+#[derive(Resource, Default)]
+pub struct HotSeat {
+    pub players: Vec<HotSeatPlayer>,
+    pub turn: usize,
+    // Each player's scores go to the leaderboard under their own name
+    pub post_scores: bool,
+}
+
+impl HotSeat {
+    pub fn is_active(&self) -> bool {
+        !self.players.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&HotSeatPlayer> {
+        self.players.get(self.turn)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut HotSeatPlayer> {
+        self.players.get_mut(self.turn)
+    }
+
+    // The name scores are posted under, when they are posted
+    pub fn leaderboard_name(&self) -> Option<&str> {
+        self.current()
+            .filter(|_| self.post_scores)
+            .map(|player| player.name.as_str())
+    }
+
+    // Hands the level to the next player, or to the standings once everyone has played
+    // it. Returns where to go, or None outside hot-seat.
+    pub fn pass_turn(&mut self, advance: &mut Advance) -> Option<AppState> {
+        if !self.is_active() {
+            return None;
+        }
+        self.turn += 1;
+        if self.turn < self.players.len() {
+            advance.0 = false;
+            Some(AppState::Game)
+        } else {
+            self.turn = 0;
+            advance.0 = true;
+            Some(AppState::HotSeatResults)
+        }
+    }
+
+    // Most levels completed first, then the fewest players beating them level by level.
+    // Each level ranks its scores its own way, so scores are only compared within a level.
+    fn standings(&self, levels: &Assets<LevelLex>) -> Vec<&HotSeatPlayer> {
+        let beaten_by = |player: &HotSeatPlayer| -> usize {
+            player
+                .score
+                .0
+                .iter()
+                .map(|(level, score)| {
+                    let scoring = level_scoring(levels, *level);
+                    self.players
+                        .iter()
+                        .filter_map(|other| other.score.0.get(level))
+                        .filter(|other| scoring.is_better(**other, *score))
+                        .count()
+                })
+                .sum()
+        };
+        let mut players = self.players.iter().collect::<Vec<_>>();
+        players.sort_by_cached_key(|player| {
+            (
+                std::cmp::Reverse(player.levels_completed()),
+                beaten_by(player),
+            )
+        });
+        players
+    }
+}
+
+#[derive(Component)]
+struct PlayerNameInput(usize);
+
+#[derive(Component)]
+struct HotSeatFeedback;
+
+#[derive(Component)]
+struct PostScoresToggle;
+
+fn end_hot_seat(mut hot_seat: ResMut<HotSeat>) {
+    hot_seat.players.clear();
+    hot_seat.turn = 0;
+}
+
+fn hot_seat_button(label: impl Into<String>, top: f32, left: f32) -> impl Bundle {
+    let label = label.into();
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            width: Val::Px(120.0),
+            border: UiRect::all(Val::Px(2.0)),
+            top: Val::Px(top),
+            left: Val::Px(left),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        Pickable::default(),
+        Text::default(),
+        BackgroundColor(DARK_COLOR),
+        TextLayout::default().with_justify(JustifyText::Center),
+        children![(
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT)
+                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
+                .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+            Pickable::IGNORE,
+            TextSpan::new(label),
+        )],
+    )
+}
+
+fn player_name_input(index: usize) -> impl Bundle {
+    (
+        StateScoped(AppState::HotSeatSetup),
+        PlayerNameInput(index),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(200.0),
+            height: Val::Px(30.0),
+            left: Val::Px(220.0),
+            top: Val::Px(120.0 + index as f32 * 45.0),
+            padding: UiRect::all(Val::Px(7.0)),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        BackgroundColor(LIGHT_COLOR),
+        TextInput,
+        TextInputInactive(index != 0),
+        TextInputSettings {
+            retain_on_submit: true,
+            ..default()
+        },
+        TextInputPlaceholder {
+            value: format!("Player {}", index + 1),
+            ..default()
+        },
+        TextInputTextFont(
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+        ),
+        TextInputTextColor(DARK_COLOR.into()),
+    )
+}
+
+fn setup_hot_seat(mut commands: Commands, hud: Res<Hud>) {
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::HotSeatSetup),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(30.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                    Text("Hot seat".into()),
+                ));
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                    Text(format!(
+                        "{} to {} players take turns at each level",
+                        HOT_SEAT_MIN_PLAYERS, HOT_SEAT_MAX_PLAYERS
+                    )),
+                ));
+            });
+
+        for index in 0..HOT_SEAT_MAX_PLAYERS {
+            parent
+                .spawn(player_name_input(index))
+                .observe(focus_player_input);
+        }
+
+        parent.spawn((
+            StateScoped(AppState::HotSeatSetup),
+            HotSeatFeedback,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(220.0),
+                top: Val::Px(300.0),
+                ..default()
+            },
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 35.),
+            Text::default(),
+        ));
+
+        parent
+            .spawn((
+                StateScoped(AppState::HotSeatSetup),
+                PostScoresToggle,
+                hot_seat_button("Leaderboard: Off", 340.0, 220.0),
+            ))
+            .observe(toggle_post_scores);
+        parent
+            .spawn((
+                StateScoped(AppState::HotSeatSetup),
+                hot_seat_button("Start", 340.0, 360.0),
+            ))
+            .observe(start_hot_seat_on_click);
+        parent
+            .spawn((
+                StateScoped(AppState::HotSeatSetup),
+                hot_seat_button("Back", 425.0, 500.0),
+            ))
+            .observe(super::go_to_menu);
+    });
+}
+
+// Only one name field takes typing at a time
+fn focus_player_input(
+    trigger: Trigger<Pointer<Click>>,
+    mut inputs: Query<(Entity, &mut TextInputInactive), With<PlayerNameInput>>,
+) {
+    for (entity, mut inactive) in &mut inputs {
+        inactive.0 = entity != trigger.target;
+    }
+}
+
+fn cycle_player_input_focus(mut inputs: Query<(&PlayerNameInput, &mut TextInputInactive)>) {
+    let active = inputs
+        .iter()
+        .find(|(_, inactive)| !inactive.0)
+        .map(|(input, _)| input.0)
+        .unwrap_or(HOT_SEAT_MAX_PLAYERS - 1);
+    let next = (active + 1) % HOT_SEAT_MAX_PLAYERS;
+    for (input, mut inactive) in &mut inputs {
+        inactive.0 = input.0 != next;
+    }
+}
+
+// The typed names in order, blank fields skipped, or why they can't be used
+fn player_names(
+    inputs: &Query<(&PlayerNameInput, &TextInputValue)>,
+    name_rules: &NameRules,
+) -> Result<Vec<String>, String> {
+    let mut inputs = inputs.iter().collect::<Vec<_>>();
+    inputs.sort_by_key(|(input, _)| input.0);
+
+    let mut names: Vec<String> = Vec::new();
+    for (input, value) in inputs {
+        if value.0.trim().is_empty() {
+            continue;
+        }
+        let name = name_rules
+            .validate(&value.0)
+            .map_err(|e| format!("Player {}: {}", input.0 + 1, e))?;
+        if names.iter().any(|other| other.eq_ignore_ascii_case(&name)) {
+            return Err(format!("{} is already playing", name));
+        }
+        names.push(name);
+    }
+
+    if names.len() < HOT_SEAT_MIN_PLAYERS {
+        return Err(format!("Enter at least {} names", HOT_SEAT_MIN_PLAYERS));
+    }
+    Ok(names)
+}
+
+fn update_hot_seat_feedback(
+    inputs: Query<(&PlayerNameInput, &TextInputValue)>,
+    mut feedback: Query<&mut Text, With<HotSeatFeedback>>,
+    name_rules: Local<NameRules>,
+) {
+    let Ok(mut text) = feedback.single_mut() else {
+        return;
+    };
+    text.0 = match player_names(&inputs, &name_rules) {
+        Ok(names) => format!("{} players ready", names.len()),
+        Err(message) => message,
+    };
+}
+
+fn toggle_post_scores(_: Trigger<Pointer<Click>>, mut hot_seat: ResMut<HotSeat>) {
+    hot_seat.post_scores = !hot_seat.post_scores;
+}
+
+fn toggle_post_scores_label(
+    hot_seat: Res<HotSeat>,
+    toggle: Query<&Children, With<PostScoresToggle>>,
+    mut spans: Query<&mut TextSpan>,
+) {
+    let Some(&child) = toggle
+        .iter()
+        .flatten()
+        .find(|&&child| spans.contains(child))
+    else {
+        return;
+    };
+    let Ok(mut span) = spans.get_mut(child) else {
+        return;
+    };
+    span.0 = match hot_seat.post_scores {
+        true => "Leaderboard: On".into(),
+        false => "Leaderboard: Off".into(),
+    };
+}
+
+fn start_hot_seat(
+    commands: &mut Commands,
+    inputs: &Query<(&PlayerNameInput, &TextInputValue)>,
+    hot_seat: &mut HotSeat,
+    current_level_id: &mut CurrentLevelId,
+    advance: &mut Advance,
+    game_state: &mut NextState<GameState>,
+) {
+    let Ok(names) = player_names(inputs, &NameRules::default()) else {
+        return;
+    };
+    hot_seat.players = names
+        .into_iter()
+        .map(|name| HotSeatPlayer {
+            name,
+            score: PlayerScore::default(),
+        })
+        .collect();
+    hot_seat.turn = 0;
+    current_level_id.0 = 0;
+    advance.0 = false;
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::Game));
+}
+
+fn start_hot_seat_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    inputs: Query<(&PlayerNameInput, &TextInputValue)>,
+    mut hot_seat: ResMut<HotSeat>,
+    mut current_level_id: ResMut<CurrentLevelId>,
+    mut advance: ResMut<Advance>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    start_hot_seat(
+        &mut commands,
+        &inputs,
+        &mut hot_seat,
+        &mut current_level_id,
+        &mut advance,
+        &mut game_state,
+    );
+}
+
+fn start_hot_seat_on_enter(
+    mut commands: Commands,
+    inputs: Query<(&PlayerNameInput, &TextInputValue)>,
+    mut hot_seat: ResMut<HotSeat>,
+    mut current_level_id: ResMut<CurrentLevelId>,
+    mut advance: ResMut<Advance>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    start_hot_seat(
+        &mut commands,
+        &inputs,
+        &mut hot_seat,
+        &mut current_level_id,
+        &mut advance,
+        &mut game_state,
+    );
+}
+
+fn show_turn(mut commands: Commands, hud: Res<Hud>, hot_seat: Res<HotSeat>) {
+    let Some(player) = hot_seat.current() else {
+        return;
+    };
+    commands.entity(hud.0).with_children(|parent| {
+        parent.spawn((
+            StateScoped(AppState::Game),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(45.0),
+                left: Val::Px(45.0),
+                ..default()
+            },
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+            Text(format!("{}'s turn", player.name)),
+        ));
+    });
+}
+
+fn is_last_level(current_level_id: &CurrentLevelId, levels: &Assets<LevelLex>) -> bool {
+    let level_count = levels
+        .iter()
+        .next()
+        .map(|(_, data)| data.levels.len())
+        .unwrap_or_default();
+    current_level_id.0 + 1 >= level_count
+}
+
+fn setup_hot_seat_results(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    hot_seat: Res<HotSeat>,
+    current_level_id: Res<CurrentLevelId>,
+    levels: Res<Assets<LevelLex>>,
+) {
+    let finished = is_last_level(&current_level_id, &levels);
+
+    let title = match finished {
+        true => "Final results".to_string(),
+        false => format!("Level {} results", current_level_id.0 + 1),
+    };
+    let standings = hot_seat
+        .standings(&levels)
+        .iter()
+        .enumerate()
+        .map(|(index, player)| {
+            format!(
+                "{}. {}  {} ({} levels)",
+                index + 1,
+                player.name,
+                player.total(),
+                player.levels_completed()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::HotSeatResults),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(55.0),
+                    left: Val::Px(120.0),
+                    align_items: AlignItems::Start,
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                    Text(title),
+                ));
+                p.spawn(spacer());
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                    Text(standings),
+                ));
+            });
+
+        if finished {
+            parent
+                .spawn((
+                    StateScoped(AppState::HotSeatResults),
+                    hot_seat_button("Menu", 425.0, 500.0),
+                ))
+                .observe(super::back_to_menu_and_reset);
+        } else {
+            parent
+                .spawn((
+                    StateScoped(AppState::HotSeatResults),
+                    hot_seat_button("Next level", 350.0, 500.0),
+                ))
+                .observe(continue_hot_seat);
+            parent
+                .spawn((
+                    StateScoped(AppState::HotSeatResults),
+                    hot_seat_button("Back", 425.0, 500.0),
+                ))
+                .observe(super::back_to_menu_and_reset);
+        }
+    });
+}
+
+fn continue_hot_seat(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::Game));
+}
+
+// Past the last level, Space heads back to the menu like the final results' button
+fn continue_hot_seat_on_spacebar(
+    mut commands: Commands,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut current_level_id: ResMut<CurrentLevelId>,
+    mut advance: ResMut<Advance>,
+    levels: Res<Assets<LevelLex>>,
+) {
+    if is_last_level(&current_level_id, &levels) {
+        current_level_id.0 = 0;
+        advance.0 = false;
+        game_state.set(GameState::Reset);
+        commands.send_event(SceneChange(AppState::Menu));
+        return;
+    }
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::Game));
+}
@@ -0,0 +1,166 @@
+use super::{LoadedLevel, setup as setup_game};
+use crate::app::{AppState, LIGHT_COLOR, RESOLUTION_HEIGHT};
+use crate::assets::custom::ImageAssets;
+use crate::util::handles::BODY_FONT;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use weed_core::{PowerUp, Run};
+
+// Tint of a letterbox whose letter gives a power-up
+pub(super) const GOLDEN: Color = Color::srgb(1.0, 0.8, 0.25);
+
+// Clear and heal happen at once, their icons stay lit this long afterwards
+const FLASH_MS: u32 = 1500;
+
+// An icon per power-up the level can give, lit with a countdown while it's in effect
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppState::Game),
+        setup_power_up_display.after(setup_game),
+    )
+    .add_systems(
+        Update,
+        update_power_up_display.run_if(in_state(AppState::Game)),
+    );
+}
+
+#[derive(Component)]
+struct PowerUpIcon(PowerUp);
+
+#[derive(Component)]
+struct PowerUpTimer(PowerUp);
+
+// The power-up's initial in the letters sheet
+fn letter_index(power_up: PowerUp) -> usize {
+    match power_up {
+        PowerUp::FreezeTimer => 5,
+        PowerUp::ClearWeeds => 2,
+        PowerUp::RestoreHealth => 7,
+    }
+}
+
+fn active_ms(run: &Run, power_up: PowerUp) -> u32 {
+    match power_up {
+        PowerUp::FreezeTimer => run.frozen_ms(),
+        _ => run
+            .power_ups()
+            .iter()
+            .rev()
+            .find(|(used, _)| *used == power_up)
+            .map_or(0, |(_, at)| {
+                (at + FLASH_MS).saturating_sub(run.elapsed_ms())
+            }),
+    }
+}
+
+fn setup_power_up_display(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    image_assets: Res<ImageAssets>,
+    loaded_level: Res<LoadedLevel>,
+) {
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+    let enabled = &level.run.rules().power_ups;
+    if enabled.is_empty() {
+        return;
+    }
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::Game),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    top: Val::Px(80.0),
+                    left: Val::Px(45.0),
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|p| {
+                for power_up in PowerUp::ALL.into_iter().filter(|p| enabled.contains(p)) {
+                    p.spawn(Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|column| {
+                        column
+                            .spawn((
+                                PowerUpIcon(power_up),
+                                Node {
+                                    width: Val::Px(32.0),
+                                    height: Val::Px(32.0),
+                                    ..default()
+                                },
+                                ImageNode {
+                                    image: image_assets.letterbox.clone(),
+                                    color: GOLDEN,
+                                    texture_atlas: Some(TextureAtlas {
+                                        layout: image_assets.letterbox_layout.clone(),
+                                        index: 4,
+                                    }),
+                                    ..default()
+                                },
+                            ))
+                            .with_children(|icon| {
+                                icon.spawn((
+                                    Node {
+                                        position_type: PositionType::Absolute,
+                                        width: Val::Px(14.0),
+                                        height: Val::Px(14.0),
+                                        left: Val::Px(9.0),
+                                        top: Val::Px(9.0),
+                                        ..default()
+                                    },
+                                    ImageNode {
+                                        image: image_assets.letters.clone(),
+                                        texture_atlas: Some(TextureAtlas {
+                                            layout: image_assets.letters_layout.clone(),
+                                            index: letter_index(power_up),
+                                        }),
+                                        ..default()
+                                    },
+                                ));
+                            });
+                        column.spawn((
+                            PowerUpTimer(power_up),
+                            TextColor(LIGHT_COLOR),
+                            TextFont::from_font(BODY_FONT)
+                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 40.),
+                            Text(power_up.label().into()),
+                        ));
+                    });
+                }
+            });
+    });
+}
+
+fn update_power_up_display(
+    loaded_level: Res<LoadedLevel>,
+    mut icons: Query<(&PowerUpIcon, &mut ImageNode)>,
+    mut timers: Query<(&PowerUpTimer, &mut Text)>,
+) {
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+
+    for (icon, mut image) in &mut icons {
+        image.color = match active_ms(&level.run, icon.0) {
+            0 => GOLDEN.with_alpha(0.35),
+            _ => GOLDEN,
+        };
+    }
+    for (timer, mut text) in &mut timers {
+        text.0 = match active_ms(&level.run, timer.0) {
+            0 => timer.0.label().into(),
+            ms => format!("{:.1}s", ms as f32 / 1000.0),
+        };
+    }
+}
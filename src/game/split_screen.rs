@@ -0,0 +1,575 @@
+use super::{
+    ActiveKey, ActiveKeyMarker, CurrentLevelId, KeyMap, KeyPosition, Letterbox, Level,
+    MIN_PLACEMENT_DISTANCE, PlantGrowth, SfxMusicVolume, Weed, WeedTracker, animate_key,
+    go_to_menu, sfx_setup,
+};
+use crate::app::{AppState, DARK_COLOR, LIGHT_COLOR, RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
+use crate::assets::custom::{ImageAssets, SoundAssets};
+use crate::assets::lexi::levels::LevelLex;
+use crate::controls::{Action, Bindings, action_just_pressed, key_label};
+use crate::util::handles::BODY_FONT;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use std::time::Duration;
+use weed_core::{LevelRules, Outcome, Press};
+
+// Visible weeds in each garden, the rest grow in as they're cleared
+const MAX_VISIBLE_WEEDS_PER_SIDE: u32 = 5;
+const WEED_X_ATTEMPTS: usize = 20;
+
+// Two players on one keyboard, each clearing their own garden with their half of the
+// letters
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SplitRace::default())
+        .add_systems(
+            OnEnter(AppState::SplitScreen),
+            (sfx_setup, setup_split_screen),
+        )
+        .add_systems(
+            Update,
+            (
+                start_split_race.run_if(action_just_pressed(Action::Start)),
+                split_race_clock,
+                split_keypress_events.run_if(on_event::<KeyboardInput>),
+                update_side_displays,
+                update_split_banner,
+                animate_key,
+            )
+                .chain()
+                .run_if(in_state(AppState::SplitScreen)),
+        );
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    // Keys left of the middle of the on screen keyboard belong to the left player
+    fn of(key_position: &KeyPosition, c: char) -> Option<Self> {
+        let position = key_position.0.get(&c)?;
+        Some(if position.x < 0.0 {
+            Self::Left
+        } else {
+            Self::Right
+        })
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Right => "Right",
+        }
+    }
+
+    // Multiplies x positions so each garden stays on its own half
+    fn sign(self) -> f32 {
+        match self {
+            Self::Left => -1.0,
+            Self::Right => 1.0,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RaceStatus {
+    #[default]
+    Waiting,
+    Running,
+    Won(Side),
+}
+
+// Both runs share one clock so neither side gets a head start
+#[derive(Resource, Default)]
+pub struct SplitRace {
+    pub status: RaceStatus,
+    elapsed: Duration,
+}
+
+impl SplitRace {
+    fn now_ms(&self) -> u32 {
+        self.elapsed.as_millis() as u32
+    }
+}
+
+// Each side's rose and HUD line
+#[derive(Component)]
+struct SideRose;
+
+#[derive(Component)]
+struct SideDisplay;
+
+#[derive(Component)]
+struct SplitBanner;
+
+// The level's letters on one side of the keyboard, or every letter on that side when
+// the level has none there. Both sides only get single lowercase keys and plain weeds,
+// without golden letters.
+fn side_rules(rules: &LevelRules, side: Side, key_position: &KeyPosition) -> LevelRules {
+    let on_side = |letter: &String| {
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_lowercase() => Side::of(key_position, c) == Some(side),
+            _ => false,
+        }
+    };
+    let mut letters = rules
+        .letters
+        .iter()
+        .filter(|letter| on_side(letter))
+        .cloned()
+        .collect::<Vec<_>>();
+    if letters.is_empty() {
+        letters = key_position
+            .0
+            .keys()
+            .map(|c| c.to_string())
+            .filter(|letter| on_side(letter))
+            .collect();
+        letters.sort();
+    }
+    LevelRules {
+        letters,
+        weeds: Vec::new(),
+        power_ups: Vec::new(),
+        ..rules.clone()
+    }
+}
+
+fn weed_sprite(image_assets: &ImageAssets, rng: &mut impl Rng, index: usize) -> Sprite {
+    let weeds = [
+        (&image_assets.weed1, &image_assets.weed1_layout),
+        (&image_assets.weed2, &image_assets.weed2_layout),
+        (&image_assets.weed3, &image_assets.weed3_layout),
+        (&image_assets.weed4, &image_assets.weed4_layout),
+        (&image_assets.weed5, &image_assets.weed5_layout),
+    ];
+    let (image, layout) = weeds.choose(rng).unwrap();
+    Sprite {
+        flip_x: rng.random_bool(0.5),
+        image: (*image).clone(),
+        texture_atlas: Some(TextureAtlas {
+            layout: (*layout).clone(),
+            index,
+        }),
+        ..default()
+    }
+}
+
+// A spot in the side's half that isn't on top of another weed. A half only fits a
+// handful of weeds apart, so after a few tries the least crowded spot will do.
+fn weed_x(side: Side, taken: &[f32], rng: &mut impl Rng) -> f32 {
+    let gap = |x: f32| {
+        taken
+            .iter()
+            .map(|other| (other - x).abs())
+            .fold(f32::INFINITY, f32::min)
+    };
+    let mut best = (f32::NEG_INFINITY, 0.0);
+    for _ in 0..WEED_X_ATTEMPTS {
+        let x = side.sign() * rng.random_range(50.0..=RESOLUTION_WIDTH / 2.0 - 20.0);
+        let gap = gap(x);
+        if gap >= MIN_PLACEMENT_DISTANCE {
+            return x;
+        }
+        if gap > best.0 {
+            best = (gap, x);
+        }
+    }
+    best.1
+}
+
+fn setup_split_screen(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    image_assets: Res<ImageAssets>,
+    levels: Res<Assets<LevelLex>>,
+    current_level_id: Res<CurrentLevelId>,
+    key_position: Res<KeyPosition>,
+    mut split_race: ResMut<SplitRace>,
+) {
+    *split_race = SplitRace::default();
+
+    let Some(level_info) = levels
+        .iter()
+        .next()
+        .and_then(|(_, data)| data.levels.get(current_level_id.0))
+    else {
+        return;
+    };
+    let rules = level_info.rules();
+    let mut rng = rand::rng();
+    let seed = rng.random();
+
+    for side in [Side::Left, Side::Right] {
+        let mut weed_tracker = WeedTracker {
+            max: MAX_VISIBLE_WEEDS_PER_SIDE.min(rules.count),
+            ..default()
+        };
+        let mut taken = Vec::new();
+        for _ in 0..weed_tracker.max {
+            let x = weed_x(side, &taken, &mut rng);
+            taken.push(x);
+            commands.spawn((
+                StateScoped(AppState::SplitScreen),
+                side,
+                Weed::default(),
+                Transform::from_translation(Vec3::new(x, -20.0, 0.0)),
+                weed_sprite(&image_assets, &mut rng, 4),
+            ));
+            weed_tracker.visible += 1;
+        }
+        weed_tracker.non_visible = rules.count.saturating_sub(weed_tracker.visible);
+
+        commands.spawn((
+            StateScoped(AppState::SplitScreen),
+            side,
+            Level::new(
+                side_rules(&rules, side, &key_position),
+                seed,
+                current_level_id.0,
+            ),
+            weed_tracker,
+            ActiveKey::default(),
+        ));
+
+        commands.spawn((
+            StateScoped(AppState::SplitScreen),
+            side,
+            SideRose,
+            Transform::from_translation(Vec3::new(side.sign() * 160.0, -40.0, -10.0)),
+            Sprite {
+                image: image_assets.rose.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: image_assets.rose_layout.clone(),
+                    index: 0,
+                }),
+                ..default()
+            },
+        ));
+    }
+
+    commands.entity(hud.0).with_children(|parent| {
+        for side in [Side::Left, Side::Right] {
+            parent.spawn((
+                StateScoped(AppState::SplitScreen),
+                side,
+                SideDisplay,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(15.0),
+                    left: Val::Px(match side {
+                        Side::Left => 45.0,
+                        Side::Right => RESOLUTION_WIDTH / 2.0 + 45.0,
+                    }),
+                    ..default()
+                },
+                TextColor(LIGHT_COLOR),
+                TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                Text::default(),
+            ));
+        }
+
+        // The dividing line between the gardens
+        parent.spawn((
+            StateScoped(AppState::SplitScreen),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(RESOLUTION_WIDTH / 2.0 - 1.0),
+                top: Val::Px(0.0),
+                width: Val::Px(2.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(LIGHT_COLOR.with_alpha(0.4)),
+        ));
+
+        parent
+            .spawn((
+                StateScoped(AppState::SplitScreen),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(100.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    SplitBanner,
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                    Text::default(),
+                ));
+            });
+
+        parent
+            .spawn((
+                StateScoped(AppState::SplitScreen),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Px(100.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    top: Val::Px(425.0),
+                    left: Val::Px(500.0),
+                    ..default()
+                },
+                BorderColor(LIGHT_COLOR),
+                BorderRadius::MAX,
+                Pickable::default(),
+                Text::default(),
+                BackgroundColor(DARK_COLOR),
+                TextLayout::default().with_justify(JustifyText::Center),
+                children![(
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
+                        .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+                    Pickable::IGNORE,
+                    TextSpan::new("Back"),
+                )],
+            ))
+            .observe(go_to_menu);
+    });
+}
+
+fn spawn_letterbox(
+    commands: &mut Commands,
+    image_assets: &ImageAssets,
+    key_position: &KeyPosition,
+    side: Side,
+    key: &str,
+) {
+    let position = key_position
+        .0
+        .get(&key.chars().next().unwrap_or_default())
+        .cloned()
+        .unwrap_or(Vec3::new(1000.0, 1000.0, -1000.0));
+    commands.spawn((
+        StateScoped(AppState::SplitScreen),
+        side,
+        Letterbox::new(key),
+        Transform::from_translation(position),
+        Sprite {
+            image: image_assets.letterbox.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: image_assets.letterbox_layout.clone(),
+                index: 0,
+            }),
+            ..default()
+        },
+    ));
+}
+
+// Space starts the race, and once it's won starts a rematch on a fresh pair of gardens
+fn start_split_race(
+    mut commands: Commands,
+    mut split_race: ResMut<SplitRace>,
+    image_assets: Res<ImageAssets>,
+    key_position: Res<KeyPosition>,
+    mut racers: Query<(&Side, &Level, &mut ActiveKey)>,
+) {
+    match split_race.status {
+        RaceStatus::Waiting => {
+            split_race.status = RaceStatus::Running;
+            for (side, level, mut active_key) in &mut racers {
+                if let Some(key) = level.run.prompt() {
+                    active_key.set(key);
+                    spawn_letterbox(&mut commands, &image_assets, &key_position, *side, key);
+                }
+            }
+        }
+        RaceStatus::Running => {}
+        RaceStatus::Won(_) => {
+            commands.send_event(super::SceneChange(AppState::SplitScreen));
+        }
+    }
+}
+
+fn split_race_clock(
+    time: Res<Time>,
+    mut split_race: ResMut<SplitRace>,
+    mut racers: Query<(&Side, &mut Level)>,
+) {
+    if split_race.status != RaceStatus::Running {
+        return;
+    }
+    split_race.elapsed += time.delta();
+    let now = split_race.now_ms();
+    for (side, mut level) in &mut racers {
+        // Running out of time on a prompt hands the race to the other player
+        if level.run.tick(now) == Outcome::TimedOut {
+            split_race.status = RaceStatus::Won(side.other());
+        }
+    }
+}
+
+fn split_keypress_events(
+    mut commands: Commands,
+    mut events: EventReader<KeyboardInput>,
+    mut split_race: ResMut<SplitRace>,
+    key_map: Res<KeyMap>,
+    key_position: Res<KeyPosition>,
+    image_assets: Res<ImageAssets>,
+    sound_assets: Res<SoundAssets>,
+    sfx_music_volume: Res<SfxMusicVolume>,
+    mut racers: Query<(&Side, &mut Level, &mut WeedTracker, &mut ActiveKey)>,
+    mut weeds: Query<(&Side, &Transform, &mut Weed)>,
+    mut letterboxes: Query<(&Side, &mut Letterbox)>,
+) {
+    let mut rng = rand::rng();
+    for event in events.read() {
+        if split_race.status != RaceStatus::Running || event.state != ButtonState::Pressed {
+            continue;
+        }
+        // Only letters belong to a player, Shift would be shared
+        let Some(key) = key_map
+            .key_for(event.key_code, false)
+            .filter(|c| c.is_ascii_lowercase())
+        else {
+            continue;
+        };
+        let Some(side) = Side::of(&key_position, key) else {
+            continue;
+        };
+        let Some((_, mut level, mut weed_tracker, mut active_key)) =
+            racers.iter_mut().find(|(racer, ..)| **racer == side)
+        else {
+            continue;
+        };
+
+        let vol = if sfx_music_volume.sfx { 2.0 } else { 0.0 };
+        match level.run.press(split_race.now_ms(), key) {
+            Press::Correct => {
+                commands.spawn((
+                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                    AudioPlayer(sound_assets.collect_sfx.clone()),
+                ));
+                for (_, mut letterbox) in letterboxes.iter_mut().filter(|(s, _)| **s == side) {
+                    letterbox.state = ActiveKeyMarker::Out;
+                }
+
+                // Clear one of the side's weeds and grow one of the rest in its place
+                let mut standing = weeds
+                    .iter_mut()
+                    .filter(|(s, _, weed)| **s == side && !weed.is_wacked())
+                    .collect::<Vec<_>>();
+                let wacked = (!standing.is_empty()).then(|| rng.random_range(0..standing.len()));
+                if let Some((_, _, weed)) = wacked.map(|i| &mut standing[i]) {
+                    weed.plant_growth = PlantGrowth::Wacked;
+                    weed_tracker.visible = weed_tracker.visible.saturating_sub(1);
+                }
+                let taken = standing
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| Some(*i) != wacked)
+                    .map(|(_, (_, transform, _))| transform.translation.x)
+                    .collect::<Vec<_>>();
+                if weed_tracker.non_visible > 0 {
+                    weed_tracker.non_visible -= 1;
+                    weed_tracker.visible += 1;
+                    commands.spawn((
+                        StateScoped(AppState::SplitScreen),
+                        side,
+                        Weed::new(),
+                        Transform::from_translation(Vec3::new(
+                            weed_x(side, &taken, &mut rng),
+                            -20.0,
+                            0.0,
+                        )),
+                        weed_sprite(&image_assets, &mut rng, 0),
+                    ));
+                }
+
+                if level.run.outcome() == Outcome::Complete {
+                    active_key.reset();
+                    split_race.status = RaceStatus::Won(side);
+                } else if let Some(next_key) = level.run.prompt() {
+                    active_key.set(next_key);
+                    spawn_letterbox(&mut commands, &image_assets, &key_position, side, next_key);
+                }
+            }
+            Press::Wrong => {
+                commands.spawn((
+                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                    AudioPlayer(sound_assets.error.clone()),
+                ));
+                if level.run.outcome() == Outcome::TooManyWrongKeys {
+                    active_key.reset();
+                    split_race.status = RaceStatus::Won(side.other());
+                }
+            }
+            Press::Held | Press::Ignored => {}
+        }
+    }
+}
+
+fn update_side_displays(
+    racers: Query<(&Side, &Level)>,
+    mut displays: Query<(&Side, &mut Text), With<SideDisplay>>,
+    mut roses: Query<(&Side, &mut Sprite), With<SideRose>>,
+) {
+    for (side, level) in &racers {
+        let weeds_left = level.run.weeds_left();
+        let wrong_left = level
+            .run
+            .rules()
+            .fail_count
+            .saturating_sub(level.run.fail_count());
+
+        for (_, mut text) in displays.iter_mut().filter(|(s, _)| *s == side) {
+            text.0 = format!(
+                "{}  Left: {}  Misses: {}",
+                side.label(),
+                weeds_left,
+                wrong_left
+            );
+        }
+        for (_, mut sprite) in roses.iter_mut().filter(|(s, _)| *s == side) {
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = match level.run.outcome() {
+                    Outcome::Complete => 9,
+                    _ => 9 - weeds_left.min(9) as usize,
+                };
+            }
+        }
+    }
+}
+
+fn update_split_banner(
+    split_race: Res<SplitRace>,
+    bindings: Res<Bindings>,
+    mut banner: Query<&mut Text, With<SplitBanner>>,
+) {
+    let Ok(mut text) = banner.single_mut() else {
+        return;
+    };
+    text.0 = match split_race.status {
+        RaceStatus::Waiting => bindings.prompt(Action::Start, "Start"),
+        RaceStatus::Running => String::new(),
+        RaceStatus::Won(side) => format!(
+            "{} player wins!\n{} for a rematch",
+            side.label(),
+            key_label(bindings.key(Action::Start))
+        ),
+    };
+}
@@ -0,0 +1,240 @@
+use super::{ClickMode, KeyMap, KeyPosition};
+use crate::app::{AppState, LIGHT_COLOR, RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
+use crate::controls::{Action, Bindings};
+use crate::util::handles::BODY_FONT;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_aspect_ratio_mask::Hud;
+
+pub(super) const KEY_SIZE: f32 = 50.0;
+
+// Keys drawn over the letter positions for phones and tablets. A tap sends the same
+// KeyboardInput a physical key would, so the game can't tell them apart.
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(TouchKeyboard::default())
+        .add_systems(Update, detect_touch)
+        .add_systems(
+            Update,
+            (show_touch_keyboard, update_shift_key).run_if(
+                in_state(AppState::Game)
+                    .or(in_state(AppState::GameOver))
+                    .or(in_state(AppState::LoadNextLevel)),
+            ),
+        );
+}
+
+#[derive(Resource, Default)]
+pub struct TouchKeyboard {
+    pub enabled: bool,
+    // Shift stays down until it's tapped again
+    shift: bool,
+}
+
+#[derive(Component)]
+struct TouchKeyboardRoot;
+
+#[derive(Component)]
+struct TouchKey(KeyCode);
+
+// The first touch switches the keyboard on for the rest of the session
+fn detect_touch(touches: Res<Touches>, mut touch_keyboard: ResMut<TouchKeyboard>) {
+    if !touch_keyboard.enabled && touches.any_just_pressed() {
+        touch_keyboard.enabled = true;
+    }
+}
+
+// From a position in the game world to one in the 640 by 480 HUD
+pub(super) fn key_node(center: Vec2, width: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        left: Val::Px(center.x + RESOLUTION_WIDTH / 2.0 - width / 2.0),
+        top: Val::Px(RESOLUTION_HEIGHT / 2.0 - center.y - KEY_SIZE / 2.0),
+        width: Val::Px(width),
+        height: Val::Px(KEY_SIZE),
+        border: UiRect::all(Val::Px(1.0)),
+        justify_content: JustifyContent::End,
+        align_items: AlignItems::End,
+        padding: UiRect::all(Val::Px(3.0)),
+        ..default()
+    }
+}
+
+// Only the outline and a small label, the letterbox under a key has to show through
+pub(super) fn spawn_key(
+    parent: &mut ChildSpawnerCommands,
+    key_code: KeyCode,
+    label: &str,
+    node: Node,
+) {
+    parent
+        .spawn((
+            TouchKey(key_code),
+            node,
+            BackgroundColor(Color::NONE),
+            BorderColor(LIGHT_COLOR.with_alpha(0.35)),
+            BorderRadius::all(Val::Px(6.0)),
+            children![(
+                Pickable::IGNORE,
+                TextColor(LIGHT_COLOR.with_alpha(0.6)),
+                TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 40.),
+                Text(label.to_string()),
+            )],
+        ))
+        .observe(tap_key);
+}
+
+fn show_touch_keyboard(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    touch_keyboard: Res<TouchKeyboard>,
+    click_mode: Res<ClickMode>,
+    state: Res<State<AppState>>,
+    key_map: Res<KeyMap>,
+    key_position: Res<KeyPosition>,
+    bindings: Res<Bindings>,
+    shown: Query<(), With<TouchKeyboardRoot>>,
+) {
+    // Click mode draws its own keys
+    if !touch_keyboard.enabled || click_mode.0 || !shown.is_empty() {
+        return;
+    }
+
+    let state = state.get().clone();
+    let playing = state == AppState::Game;
+    let y_space = -RESOLUTION_HEIGHT / 2.0 + KEY_SIZE / 2.0 + 10.0;
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(state),
+                TouchKeyboardRoot,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|p| {
+                // Only Start is needed to move on from the end of a level
+                if playing {
+                    for (c, position) in &key_position.0 {
+                        let Some((key_code, _)) = key_map.0.get(c) else {
+                            continue;
+                        };
+                        spawn_key(
+                            p,
+                            *key_code,
+                            &c.to_string(),
+                            key_node(position.truncate(), KEY_SIZE),
+                        );
+                    }
+                    spawn_key(
+                        p,
+                        KeyCode::ShiftLeft,
+                        "shift",
+                        key_node(
+                            Vec2::new(-RESOLUTION_WIDTH / 2.0 + 50.0, y_space),
+                            KEY_SIZE * 1.5,
+                        ),
+                    );
+                }
+                spawn_key(
+                    p,
+                    bindings.key(Action::Start),
+                    "start",
+                    key_node(Vec2::new(0.0, y_space), KEY_SIZE * 5.0),
+                );
+            });
+    });
+}
+
+// A key press or release as if it came from a physical keyboard
+pub(super) fn keyboard_input(
+    key_code: KeyCode,
+    state: ButtonState,
+    window: Entity,
+) -> KeyboardInput {
+    KeyboardInput {
+        key_code,
+        logical_key: match key_code {
+            KeyCode::Space => Key::Space,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Escape => Key::Escape,
+            KeyCode::ShiftLeft => Key::Shift,
+            _ => Key::Unidentified(NativeKey::Unidentified),
+        },
+        state,
+        text: None,
+        repeat: false,
+        window,
+    }
+}
+
+// Presses then lets go of every key, with Shift held around them when any of them needs
+// it. Shift is held straight away, its event would only reach the input state next frame.
+pub(super) fn type_keys(
+    pressed: &[(KeyCode, bool)],
+    keys: &mut ButtonInput<KeyCode>,
+    keyboard_events: &mut EventWriter<KeyboardInput>,
+    window: Entity,
+) {
+    let shift = pressed.iter().any(|(_, shift)| *shift);
+    if shift {
+        keys.press(KeyCode::ShiftLeft);
+    }
+    for (key_code, _) in pressed {
+        keyboard_events.write(keyboard_input(*key_code, ButtonState::Pressed, window));
+    }
+    for (key_code, _) in pressed {
+        keyboard_events.write(keyboard_input(*key_code, ButtonState::Released, window));
+    }
+    if shift {
+        keyboard_events.write(keyboard_input(
+            KeyCode::ShiftLeft,
+            ButtonState::Released,
+            window,
+        ));
+    }
+}
+
+fn tap_key(
+    trigger: Trigger<Pointer<Pressed>>,
+    keys: Query<&TouchKey>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut touch_keyboard: ResMut<TouchKeyboard>,
+    mut keyboard_events: EventWriter<KeyboardInput>,
+) {
+    let (Ok(key), Ok(window)) = (keys.get(trigger.target), window.single()) else {
+        return;
+    };
+
+    if key.0 == KeyCode::ShiftLeft {
+        touch_keyboard.shift = !touch_keyboard.shift;
+        let state = match touch_keyboard.shift {
+            true => ButtonState::Pressed,
+            false => ButtonState::Released,
+        };
+        keyboard_events.write(keyboard_input(key.0, state, window));
+        return;
+    }
+    keyboard_events.write(keyboard_input(key.0, ButtonState::Pressed, window));
+    keyboard_events.write(keyboard_input(key.0, ButtonState::Released, window));
+}
+
+fn update_shift_key(
+    touch_keyboard: Res<TouchKeyboard>,
+    mut keys: Query<(&TouchKey, &mut BackgroundColor)>,
+) {
+    for (key, mut background) in &mut keys {
+        if key.0 == KeyCode::ShiftLeft {
+            background.0 = match touch_keyboard.shift {
+                true => LIGHT_COLOR.with_alpha(0.3),
+                false => Color::NONE,
+            };
+        }
+    }
+}
@@ -0,0 +1,619 @@
+use super::{
+    Advance, CurrentLevelId, GameState, Level, LoadedLevel, RunRecord, SceneChange,
+    setup as setup_game,
+};
+use crate::app::{AppState, DARK_COLOR, LIGHT_COLOR, RESOLUTION_HEIGHT};
+use crate::controls::{Action, action_just_pressed};
+use crate::menu::LeaderboardName;
+use crate::util::handles::BODY_FONT;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+use bevy_simple_text_input::{
+    TextInput, TextInputInactive, TextInputPlaceholder, TextInputSettings, TextInputTextColor,
+    TextInputTextFont, TextInputValue,
+};
+use weed_core::{Outcome, RaceMessage, Scoring};
+
+mod socket;
+
+use socket::RaceSocket;
+
+// Where the relay from relay/ is listening, it can be changed in the lobby to race
+// across a LAN
+pub const RELAY_URL: &str = match option_env!("RELAY_URL") {
+    Some(url) => url,
+    None => "ws://127.0.0.1:4000",
+};
+
+// Two players on different machines race the same seeded level, each seeing the other's
+// weeds left and finishing time
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Versus::default())
+        .add_event::<RaceProgress>()
+        .add_systems(OnEnter(AppState::Menu), end_versus)
+        .add_systems(OnEnter(AppState::VersusLobby), setup_lobby)
+        .add_systems(
+            Update,
+            (
+                cycle_lobby_input_focus.run_if(input_just_pressed(KeyCode::Tab)),
+                join_room_on_enter.run_if(action_just_pressed(Action::Confirm)),
+                update_lobby_status,
+            )
+                .run_if(in_state(AppState::VersusLobby)),
+        )
+        .add_systems(Update, (receive_race_messages, send_race_progress))
+        .add_systems(OnEnter(AppState::Game), start_race.after(setup_game))
+        .add_systems(
+            OnEnter(AppState::LoadNextLevel),
+            (send_finished, spawn_versus_display).chain(),
+        )
+        .add_systems(
+            OnEnter(AppState::GameOver),
+            (send_finished, spawn_versus_display).chain(),
+        )
+        .add_systems(
+            OnEnter(AppState::Game),
+            spawn_versus_display.after(start_race),
+        )
+        .add_systems(Update, update_versus_display);
+}
+
+// Sent by keypress_events after every key that counts, passed on to the opponent
+#[derive(Event)]
+pub struct RaceProgress {
+    pub weeds_left: u32,
+    pub fail_count: u32,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+enum Lobby {
+    #[default]
+    Idle,
+    Waiting,
+    RoomFull,
+    Unreachable,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RunResult {
+    complete: bool,
+    time_ms: u32,
+    score: u32,
+}
+
+impl RunResult {
+    // Completing beats not completing, then the better score
+    fn beats(&self, other: &Self, scoring: &Scoring) -> bool {
+        match (self.complete, other.complete) {
+            (true, false) => true,
+            (true, true) => scoring.is_better(self.score, other.score),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Opponent {
+    Ready,
+    Racing { weeds_left: u32, fail_count: u32 },
+    Finished(RunResult),
+    Left,
+}
+
+struct Race {
+    opponent_name: String,
+    opponent: Opponent,
+    level: usize,
+    seed: u64,
+    scoring: Scoring,
+    started: bool,
+    finished: Option<RunResult>,
+}
+
+#[derive(Resource, Default)]
+pub struct Versus {
+    lobby: Lobby,
+    race: Option<Race>,
+}
+
+impl Versus {
+    // Between the relay starting the race and this player's run ending
+    pub fn is_racing(&self) -> bool {
+        self.race
+            .as_ref()
+            .is_some_and(|race| race.finished.is_none())
+    }
+}
+
+#[derive(Component)]
+struct RelayInput;
+
+#[derive(Component)]
+struct RoomInput;
+
+#[derive(Component)]
+struct LobbyStatus;
+
+#[derive(Component)]
+struct VersusDisplay;
+
+fn close_socket(commands: &mut Commands) {
+    commands.queue(|world: &mut World| {
+        world.remove_non_send_resource::<RaceSocket>();
+    });
+}
+
+fn end_versus(mut commands: Commands, mut versus: ResMut<Versus>) {
+    *versus = Versus::default();
+    close_socket(&mut commands);
+}
+
+fn lobby_button(label: impl Into<String>, top: f32, left: f32) -> impl Bundle {
+    let label = label.into();
+    (
+        StateScoped(AppState::VersusLobby),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            width: Val::Px(120.0),
+            border: UiRect::all(Val::Px(2.0)),
+            top: Val::Px(top),
+            left: Val::Px(left),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        Pickable::default(),
+        Text::default(),
+        BackgroundColor(DARK_COLOR),
+        TextLayout::default().with_justify(JustifyText::Center),
+        children![(
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT)
+                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
+                .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+            Pickable::IGNORE,
+            TextSpan::new(label),
+        )],
+    )
+}
+
+fn lobby_input(top: f32, placeholder: &str, value: &str, active: bool) -> impl Bundle {
+    (
+        StateScoped(AppState::VersusLobby),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(300.0),
+            height: Val::Px(30.0),
+            left: Val::Px(220.0),
+            top: Val::Px(top),
+            padding: UiRect::all(Val::Px(7.0)),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        BackgroundColor(LIGHT_COLOR),
+        TextInput,
+        TextInputValue(value.to_string()),
+        TextInputInactive(!active),
+        TextInputSettings {
+            retain_on_submit: true,
+            ..default()
+        },
+        TextInputPlaceholder {
+            value: placeholder.to_string(),
+            ..default()
+        },
+        TextInputTextFont(
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+        ),
+        TextInputTextColor(DARK_COLOR.into()),
+    )
+}
+
+fn setup_lobby(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    mut versus: ResMut<Versus>,
+    current_level_id: Res<CurrentLevelId>,
+) {
+    *versus = Versus::default();
+    close_socket(&mut commands);
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::VersusLobby),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(30.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                    Text("Race online".into()),
+                ));
+                p.spawn((
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                    Text(format!(
+                        "Join the same room as your opponent to race level {}",
+                        current_level_id.0 + 1
+                    )),
+                ));
+            });
+
+        parent
+            .spawn((RelayInput, lobby_input(130.0, "Relay", RELAY_URL, false)))
+            .observe(focus_lobby_input);
+        parent
+            .spawn((RoomInput, lobby_input(175.0, "Room", "", true)))
+            .observe(focus_lobby_input);
+
+        parent.spawn((
+            StateScoped(AppState::VersusLobby),
+            LobbyStatus,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(220.0),
+                top: Val::Px(230.0),
+                ..default()
+            },
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 35.),
+            Text::default(),
+        ));
+
+        parent
+            .spawn(lobby_button("Join", 280.0, 220.0))
+            .observe(join_room_on_click);
+        parent
+            .spawn(lobby_button("Back", 425.0, 500.0))
+            .observe(super::go_to_menu);
+    });
+}
+
+fn focus_lobby_input(
+    trigger: Trigger<Pointer<Click>>,
+    mut inputs: Query<(Entity, &mut TextInputInactive), Or<(With<RelayInput>, With<RoomInput>)>>,
+) {
+    for (entity, mut inactive) in &mut inputs {
+        inactive.0 = entity != trigger.target;
+    }
+}
+
+fn cycle_lobby_input_focus(
+    mut inputs: Query<&mut TextInputInactive, Or<(With<RelayInput>, With<RoomInput>)>>,
+) {
+    for mut inactive in &mut inputs {
+        inactive.0 = !inactive.0;
+    }
+}
+
+fn join_room(
+    commands: &mut Commands,
+    versus: &mut Versus,
+    relay: &TextInputValue,
+    room: &TextInputValue,
+    leaderboard_name: &LeaderboardName,
+    current_level_id: &CurrentLevelId,
+) {
+    let room = room.0.trim().to_string();
+    if room.is_empty() || versus.lobby == Lobby::Waiting {
+        return;
+    }
+    let url = relay.0.trim().to_string();
+    let join = RaceMessage::Join {
+        room,
+        name: leaderboard_name
+            .0
+            .clone()
+            .unwrap_or_else(|| "Player".into()),
+        level: current_level_id.0,
+    };
+    commands.queue(move |world: &mut World| {
+        let mut socket = RaceSocket::connect(&url);
+        socket.send(&join);
+        world.insert_non_send_resource(socket);
+    });
+    versus.lobby = Lobby::Waiting;
+}
+
+fn join_room_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut versus: ResMut<Versus>,
+    relay: Query<&TextInputValue, With<RelayInput>>,
+    room: Query<&TextInputValue, With<RoomInput>>,
+    leaderboard_name: Res<LeaderboardName>,
+    current_level_id: Res<CurrentLevelId>,
+) {
+    let (Ok(relay), Ok(room)) = (relay.single(), room.single()) else {
+        return;
+    };
+    join_room(
+        &mut commands,
+        &mut versus,
+        relay,
+        room,
+        &leaderboard_name,
+        &current_level_id,
+    );
+}
+
+fn join_room_on_enter(
+    mut commands: Commands,
+    mut versus: ResMut<Versus>,
+    relay: Query<&TextInputValue, With<RelayInput>>,
+    room: Query<&TextInputValue, With<RoomInput>>,
+    leaderboard_name: Res<LeaderboardName>,
+    current_level_id: Res<CurrentLevelId>,
+) {
+    let (Ok(relay), Ok(room)) = (relay.single(), room.single()) else {
+        return;
+    };
+    join_room(
+        &mut commands,
+        &mut versus,
+        relay,
+        room,
+        &leaderboard_name,
+        &current_level_id,
+    );
+}
+
+fn update_lobby_status(versus: Res<Versus>, mut status: Query<&mut Text, With<LobbyStatus>>) {
+    let Ok(mut text) = status.single_mut() else {
+        return;
+    };
+    text.0 = match versus.lobby {
+        Lobby::Idle => "Enter a room name and press Enter".into(),
+        Lobby::Waiting => "Waiting for an opponent...".into(),
+        Lobby::RoomFull => "That room already has a race going".into(),
+        Lobby::Unreachable => "Couldn't reach the relay".into(),
+    };
+}
+
+fn receive_race_messages(
+    mut commands: Commands,
+    socket: Option<NonSendMut<RaceSocket>>,
+    mut versus: ResMut<Versus>,
+    mut current_level_id: ResMut<CurrentLevelId>,
+    mut advance: ResMut<Advance>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut socket) = socket else {
+        return;
+    };
+
+    while let Some(message) = socket.receive() {
+        match message {
+            RaceMessage::Start {
+                level,
+                seed,
+                opponent,
+            } => {
+                versus.race = Some(Race {
+                    opponent_name: opponent,
+                    opponent: Opponent::Ready,
+                    level,
+                    seed,
+                    scoring: Scoring::default(),
+                    started: false,
+                    finished: None,
+                });
+                current_level_id.0 = level;
+                advance.0 = false;
+                game_state.set(GameState::NotRunning);
+                commands.send_event(SceneChange(AppState::Game));
+            }
+            RaceMessage::RoomFull => {
+                versus.lobby = Lobby::RoomFull;
+                close_socket(&mut commands);
+                return;
+            }
+            RaceMessage::Progress {
+                weeds_left,
+                fail_count,
+            } => {
+                if let Some(race) = versus.race.as_mut() {
+                    race.opponent = Opponent::Racing {
+                        weeds_left,
+                        fail_count,
+                    };
+                }
+            }
+            RaceMessage::Finished {
+                complete,
+                time_ms,
+                score,
+            } => {
+                if let Some(race) = versus.race.as_mut() {
+                    race.opponent = Opponent::Finished(RunResult {
+                        complete,
+                        time_ms,
+                        score,
+                    });
+                }
+            }
+            RaceMessage::OpponentLeft => {
+                // A finished opponent's time still stands
+                let racing = versus
+                    .race
+                    .as_mut()
+                    .filter(|race| !matches!(race.opponent, Opponent::Finished(_)));
+                if let Some(race) = racing {
+                    race.opponent = Opponent::Left;
+                }
+            }
+            RaceMessage::Join { .. } => {}
+        }
+    }
+
+    if socket.is_closed() {
+        match versus.race.as_mut() {
+            Some(race) if !matches!(race.opponent, Opponent::Finished(_)) => {
+                race.opponent = Opponent::Left;
+            }
+            Some(_) => {}
+            None => versus.lobby = Lobby::Unreachable,
+        }
+        close_socket(&mut commands);
+    }
+}
+
+// Reseeds the level so both players get the same prompts. Playing the level again once
+// the race is over leaves the race.
+fn start_race(
+    mut commands: Commands,
+    mut versus: ResMut<Versus>,
+    current_level_id: Res<CurrentLevelId>,
+    mut run_record: ResMut<RunRecord>,
+    mut loaded_level: ResMut<LoadedLevel>,
+) {
+    let Some(race) = versus.race.as_mut() else {
+        return;
+    };
+    if race.started || race.level != current_level_id.0 {
+        *versus = Versus::default();
+        close_socket(&mut commands);
+        return;
+    }
+    let Some(level) = loaded_level.0.as_ref() else {
+        return;
+    };
+
+    let rules = level.run.rules().clone();
+    run_record.seed = race.seed;
+    race.scoring = rules.scoring.clone();
+    loaded_level.0 = Some(Level::new(rules, race.seed, race.level));
+    race.started = true;
+}
+
+fn send_race_progress(
+    mut events: EventReader<RaceProgress>,
+    socket: Option<NonSendMut<RaceSocket>>,
+    versus: Res<Versus>,
+) {
+    let Some(mut socket) = socket.filter(|_| versus.is_racing()) else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        socket.send(&RaceMessage::Progress {
+            weeds_left: event.weeds_left,
+            fail_count: event.fail_count,
+        });
+    }
+}
+
+fn send_finished(
+    socket: Option<NonSendMut<RaceSocket>>,
+    mut versus: ResMut<Versus>,
+    loaded_level: Res<LoadedLevel>,
+) {
+    if !versus.is_racing() {
+        return;
+    }
+    let (Some(race), Some(level)) = (versus.race.as_mut(), loaded_level.0.as_ref()) else {
+        return;
+    };
+
+    let result = RunResult {
+        complete: level.run.outcome() == Outcome::Complete,
+        time_ms: level.run.elapsed_ms(),
+        score: level.run.score(),
+    };
+    race.finished = Some(result);
+    if let Some(mut socket) = socket {
+        socket.send(&RaceMessage::Finished {
+            complete: result.complete,
+            time_ms: result.time_ms,
+            score: result.score,
+        });
+    }
+}
+
+fn spawn_versus_display(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    versus: Res<Versus>,
+    state: Res<State<AppState>>,
+) {
+    if versus.race.is_none() {
+        return;
+    }
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(state.get().clone()),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(65.0),
+                    align_items: AlignItems::End,
+                    padding: UiRect::all(Val::Px(15.)),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    VersusDisplay,
+                    TextColor(LIGHT_COLOR),
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                    Text::default(),
+                ));
+            });
+    });
+}
+
+fn update_versus_display(versus: Res<Versus>, mut display: Query<&mut Text, With<VersusDisplay>>) {
+    let Some(race) = versus.race.as_ref() else {
+        return;
+    };
+    let name = &race.opponent_name;
+    let opponent = match race.opponent {
+        Opponent::Ready => format!("{}: ready", name),
+        Opponent::Racing {
+            weeds_left,
+            fail_count,
+        } => format!("{}: {} left, {} wrong", name, weeds_left, fail_count),
+        Opponent::Finished(result) if result.complete => {
+            format!(
+                "{}: finished in {:.2}s",
+                name,
+                result.time_ms as f32 / 1000.0
+            )
+        }
+        Opponent::Finished(_) => format!("{}: out", name),
+        Opponent::Left => format!("{} left the race", name),
+    };
+    let outcome = match (race.finished, race.opponent) {
+        (Some(mine), Opponent::Finished(theirs)) if mine.beats(&theirs, &race.scoring) => {
+            "\nYou win!"
+        }
+        (Some(mine), Opponent::Finished(theirs)) if theirs.beats(&mine, &race.scoring) => {
+            "\nYou lose"
+        }
+        (Some(_), Opponent::Finished(_)) => "\nIt's a draw",
+        (Some(mine), Opponent::Left) if mine.complete => "\nYou win!",
+        _ => "",
+    };
+    for mut text in &mut display {
+        text.0 = format!("{}{}", opponent, outcome);
+    }
+}
@@ -0,0 +1,205 @@
+use weed_core::RaceMessage;
+
+// A WebSocket to the relay. Natively it runs on its own thread, on the web the browser
+// owns it, either way messages are queued until they're sent or read.
+pub struct RaceSocket {
+    inner: imp::Socket,
+    closed: bool,
+}
+
+impl RaceSocket {
+    pub fn connect(url: &str) -> Self {
+        Self {
+            inner: imp::Socket::connect(url),
+            closed: false,
+        }
+    }
+
+    pub fn send(&mut self, message: &RaceMessage) {
+        if let Ok(text) = serde_json::to_string(message) {
+            self.inner.send(text);
+        }
+    }
+
+    // The next message from the relay, messages that don't parse are skipped
+    pub fn receive(&mut self) -> Option<RaceMessage> {
+        loop {
+            match self.inner.receive() {
+                Ok(Some(text)) => {
+                    if let Ok(message) = serde_json::from_str(&text) {
+                        return Some(message);
+                    }
+                }
+                Ok(None) => return None,
+                Err(()) => {
+                    self.closed = true;
+                    return None;
+                }
+            }
+        }
+    }
+
+    // The relay couldn't be reached or hung up
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::io::ErrorKind;
+    use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
+    use std::time::Duration;
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::{Error, Message};
+
+    pub struct Socket {
+        outgoing: Sender<String>,
+        incoming: Receiver<String>,
+    }
+
+    impl Socket {
+        pub fn connect(url: &str) -> Self {
+            let (outgoing, outgoing_rx) = channel();
+            let (incoming_tx, incoming) = channel();
+            let url = url.to_string();
+            std::thread::spawn(move || run(&url, outgoing_rx, incoming_tx));
+            Self { outgoing, incoming }
+        }
+
+        pub fn send(&self, text: String) {
+            let _ = self.outgoing.send(text);
+        }
+
+        // Err once the thread has stopped and everything it read has been taken
+        pub fn receive(&self) -> Result<Option<String>, ()> {
+            match self.incoming.try_recv() {
+                Ok(text) => Ok(Some(text)),
+                Err(TryRecvError::Empty) => Ok(None),
+                Err(TryRecvError::Disconnected) => Err(()),
+            }
+        }
+    }
+
+    // Alternates between sending what the game queued and a short blocking read, until
+    // either side hangs up
+    fn run(url: &str, outgoing: Receiver<String>, incoming: Sender<String>) {
+        let Ok((mut socket, _)) = tungstenite::connect(url) else {
+            return;
+        };
+        if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+            let _ = stream.set_read_timeout(Some(Duration::from_millis(20)));
+        }
+
+        loop {
+            loop {
+                match outgoing.try_recv() {
+                    Ok(text) => {
+                        if socket.send(Message::text(text)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        let _ = socket.close(None);
+                        let _ = socket.flush();
+                        return;
+                    }
+                }
+            }
+
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    if incoming.send(text.to_string()).is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use std::sync::mpsc::{Receiver, TryRecvError, channel};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+    use web_sys::{MessageEvent, WebSocket};
+
+    pub struct Socket {
+        socket: Option<WebSocket>,
+        // Sent once the socket opens
+        pending: Vec<String>,
+        incoming: Receiver<String>,
+        _on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
+    }
+
+    impl Socket {
+        pub fn connect(url: &str) -> Self {
+            let (incoming_tx, incoming) = channel();
+            let Ok(socket) = WebSocket::new(url) else {
+                return Self {
+                    socket: None,
+                    pending: Vec::new(),
+                    incoming,
+                    _on_message: None,
+                };
+            };
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+                if let Some(text) = e.data().as_string() {
+                    let _ = incoming_tx.send(text);
+                }
+            });
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            Self {
+                socket: Some(socket),
+                pending: Vec::new(),
+                incoming,
+                _on_message: Some(on_message),
+            }
+        }
+
+        pub fn send(&mut self, text: String) {
+            self.pending.push(text);
+            self.flush();
+        }
+
+        pub fn receive(&mut self) -> Result<Option<String>, ()> {
+            self.flush();
+            match self.incoming.try_recv() {
+                Ok(text) => return Ok(Some(text)),
+                Err(TryRecvError::Disconnected) => return Err(()),
+                Err(TryRecvError::Empty) => {}
+            }
+            match &self.socket {
+                Some(socket) if socket.ready_state() != WebSocket::CLOSED => Ok(None),
+                _ => Err(()),
+            }
+        }
+
+        fn flush(&mut self) {
+            let Some(socket) = &self.socket else {
+                return;
+            };
+            if socket.ready_state() != WebSocket::OPEN {
+                return;
+            }
+            for text in self.pending.drain(..) {
+                let _ = socket.send_with_str(&text);
+            }
+        }
+    }
+
+    impl Drop for Socket {
+        fn drop(&mut self) {
+            if let Some(socket) = &self.socket {
+                socket.set_onmessage(None);
+                let _ = socket.close();
+            }
+        }
+    }
+}
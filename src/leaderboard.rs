@@ -1,33 +1,85 @@
 use crate::app::*;
 use crate::assets::custom::ImageAssets;
-use crate::game::PlayerScore;
-use crate::menu::LeaderboardName;
+use crate::assets::lexi::levels::LevelLex;
+use crate::controls::{Action, Bindings};
+use crate::game::{ClickMode, HotSeat, PlayerScore, RunRecord};
 use crate::menu::layouts::button_text;
+use crate::menu::{LeaderboardGroup, LeaderboardName};
 use crate::util::handles::BODY_FONT;
 use crate::{app::AppState, game::SceneChange};
 use bevy::prelude::*;
 use bevy_aspect_ratio_mask::Hud;
 use bevy_http_client::{HttpClient, HttpRequest, HttpResponse, HttpResponseError};
+use hmac::{Hmac, Mac};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use serde::Deserialize;
+use sha2::Sha256;
+use weed_core::{NAME_MAX_LEN, Scoring};
 
-pub const LEADERBOARD_URL: &'static str = env!("LEADERBOARD_URL");
+// Falls back to the leaderboard's local-server binary when no URL is set at build time
+pub const LEADERBOARD_URL: &str = match option_env!("LEADERBOARD_URL") {
+    Some(url) => url,
+    None => "http://127.0.0.1:3000",
+};
+pub const LEADERBOARD_LIMIT: usize = 10;
+// Must match the lambda's LEADERBOARD_SIGNING_KEY. The fallback is the key the local
+// server uses when none is set.
+const LEADERBOARD_SIGNING_KEY: &str = match option_env!("LEADERBOARD_SIGNING_KEY") {
+    Some(key) => key,
+    None => "wack-a-weed-local-development",
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_event::<GetHighScore>()
         .add_event::<PostHighScore>()
+        .add_event::<ChangeLeaderboardPage>()
+        .add_event::<ChangeLeaderboardSeason>()
+        .add_event::<ChangeLeaderboardScope>()
+        .add_event::<ChangeLeaderboardMode>()
         .insert_resource(LeaderboardLevelSelected::default())
+        .insert_resource(LeaderboardSelectionCursor::default())
+        .insert_resource(LeaderboardSelectionPage::default())
+        .insert_resource(LeaderboardSeason::default())
+        .insert_resource(LeaderboardScope::default())
+        .insert_resource(LeaderboardMode::default())
         .insert_resource(HighScores::default())
+        .insert_resource(HighScoreRank::default())
+        .insert_resource(HighScoreSubmission::default())
         .add_systems(
             OnEnter(AppState::LeaderboardSelection),
             setup_leaderboard_selection,
         )
+        .add_systems(
+            Update,
+            (
+                change_leaderboard_page.run_if(on_event::<ChangeLeaderboardPage>),
+                leaderboard_selection_navigation,
+                highlight_leaderboard_selection,
+            )
+                .run_if(in_state(AppState::LeaderboardSelection)),
+        )
         .add_systems(Update, get_high_score.run_if(on_event::<GetHighScore>))
         .add_systems(Update, post_high_score.run_if(on_event::<PostHighScore>))
         .add_systems(Update, (handle_response, handle_error))
         .add_systems(OnEnter(AppState::Leaderboard), setup_leaderboard)
         .add_systems(
             Update,
-            (update_high_scoreboard).run_if(in_state(AppState::Leaderboard)),
+            (
+                change_leaderboard_view.run_if(
+                    on_event::<ChangeLeaderboardSeason>
+                        .or(on_event::<ChangeLeaderboardScope>)
+                        .or(on_event::<ChangeLeaderboardMode>),
+                ),
+                update_high_scoreboard,
+                highlight_leaderboard_season,
+                highlight_leaderboard_scope,
+                update_leaderboard_mode_tab,
+                leaderboard_season_navigation,
+                leaderboard_scope_navigation,
+                leaderboard_mode_navigation,
+                leaderboard_back_navigation,
+            )
+                .run_if(in_state(AppState::Leaderboard)),
         );
 }
 
@@ -40,8 +92,13 @@ pub struct LeaderboardLevel(pub usize);
 fn setup_leaderboard_selection(
     mut commands: Commands,
     hud: Res<Hud>,
-    image_assets: Res<ImageAssets>,
+    leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    mut cursor: ResMut<LeaderboardSelectionCursor>,
 ) {
+    // Start on the page of the last level that was viewed
+    cursor.0 = leaderboard_level_selected.0.unwrap_or(0);
+    commands.send_event(ChangeLeaderboardPage(cursor.0 / LEVELS_PER_PAGE));
+
     commands.entity(hud.0).with_children(|parent| {
         parent
             .spawn((
@@ -108,166 +165,302 @@ fn setup_leaderboard_selection(
                     Text("Select a level to view leaders:".into()),
                 ));
             });
+    });
+}
 
-        parent
-            .spawn((
-                StateScoped(AppState::LeaderboardSelection),
-                Node {
-                    position_type: PositionType::Absolute,
-                    display: Display::Flex,
-                    flex_direction: FlexDirection::Row,
-                    width: Val::Percent(100.0),
-                    top: Val::Px(100.0),
-                    left: Val::Px(120.0),
-                    ..default()
-                },
-            ))
-            .with_children(|p| {
-                for n in 0..6 {
-                    let lvl = (n + 1).to_string();
-                    p.spawn((
-                        LeaderboardLevel(n),
-                        Node {
-                            width: Val::Px(64.0),
-                            height: Val::Px(64.0),
-                            align_content: AlignContent::Center,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::Center,
-                            justify_items: JustifyItems::Center,
-                            ..default()
-                        },
-                        ImageNode {
-                            image: image_assets.leaves.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: image_assets.leaves_layout.clone(),
-                                index: n,
-                            }),
-                            ..default()
-                        },
-                    ))
-                    .with_child((
-                        TextColor(LIGHT_COLOR),
-                        TextShadow {
-                            offset: Vec2::splat(3.0),
-                            color: DARK_COLOR,
-                        },
-                        TextFont::from_font(BODY_FONT)
-                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 14.)
-                            .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
-                        Pickable::IGNORE,
-                        Text::new(lvl),
-                    ))
-                    .observe(set_leaderboard_level_on_click);
-                }
-            });
+const LEVELS_PER_ROW: usize = 6;
+const LEVELS_PER_PAGE: usize = LEVELS_PER_ROW * 2;
+const LEAVES_FRAME_COUNT: usize = 13;
 
-        parent
-            .spawn((
-                StateScoped(AppState::LeaderboardSelection),
-                Node {
-                    position_type: PositionType::Absolute,
-                    display: Display::Flex,
-                    flex_direction: FlexDirection::Row,
-                    width: Val::Percent(100.0),
-                    top: Val::Px(200.0),
-                    left: Val::Px(120.0),
+#[derive(Resource, Default)]
+pub struct LeaderboardSelectionCursor(pub usize);
 
-                    ..default()
-                },
-            ))
-            .with_children(|p| {
-                for n in 6..12 {
-                    let lvl = (n + 1).to_string();
-                    p.spawn((
-                        LeaderboardLevel(n),
-                        Node {
-                            width: Val::Px(64.0),
-                            height: Val::Px(64.0),
-                            align_content: AlignContent::Center,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::Center,
-                            justify_items: JustifyItems::Center,
-                            ..default()
-                        },
-                        ImageNode {
-                            image: image_assets.leaves.clone(),
-                            texture_atlas: Some(TextureAtlas {
-                                layout: image_assets.leaves_layout.clone(),
-                                index: n,
-                            }),
-                            ..default()
-                        },
-                    ))
-                    .with_child((
-                        TextColor(LIGHT_COLOR),
-                        TextShadow {
-                            offset: Vec2::splat(3.0),
-                            color: DARK_COLOR,
-                        },
-                        TextFont::from_font(BODY_FONT)
-                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 14.)
-                            .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
-                        Pickable::IGNORE,
-                        Text::new(lvl),
-                    ))
-                    .observe(set_leaderboard_level_on_click);
-                }
-            });
+#[derive(Resource, Default)]
+pub struct LeaderboardSelectionPage(pub usize);
 
-        parent
-            .spawn((
-                StateScoped(AppState::LeaderboardSelection),
-                Node {
-                    position_type: PositionType::Absolute,
-                    display: Display::Flex,
-                    flex_direction: FlexDirection::Row,
-                    width: Val::Percent(100.0),
-                    top: Val::Px(300.0),
-                    left: Val::Px(280.0),
+#[derive(Event)]
+pub struct ChangeLeaderboardPage(pub usize);
 
-                    ..default()
-                },
-            ))
-            .with_children(|p| {
-                let n = 12;
-                let lvl = (n + 1).to_string();
-                p.spawn((
-                    LeaderboardLevel(n),
+#[derive(Component)]
+pub struct LeaderboardSelectionGrid;
+
+#[derive(Component)]
+pub struct LeaderboardPageButton(pub isize);
+
+// How the level's scores are ordered, from its definition in levels.json
+pub fn level_scoring(levels: &Assets<LevelLex>, level_id: usize) -> Scoring {
+    levels
+        .iter()
+        .next()
+        .and_then(|(_, data)| data.levels.get(level_id))
+        .map(|level| level.scoring.clone())
+        .unwrap_or_default()
+}
+
+fn level_count(levels: &Assets<LevelLex>) -> usize {
+    levels
+        .iter()
+        .map(|(_, data)| data.levels.len())
+        .next()
+        .unwrap_or(0)
+}
+
+fn page_count(level_count: usize) -> usize {
+    level_count.div_ceil(LEVELS_PER_PAGE).max(1)
+}
+
+fn change_leaderboard_page(
+    mut commands: Commands,
+    mut changes: EventReader<ChangeLeaderboardPage>,
+    hud: Res<Hud>,
+    image_assets: Res<ImageAssets>,
+    levels: Res<Assets<LevelLex>>,
+    mut page: ResMut<LeaderboardSelectionPage>,
+    grid: Query<Entity, With<LeaderboardSelectionGrid>>,
+) {
+    let Some(event) = changes.read().last() else {
+        return;
+    };
+
+    let level_count = level_count(&levels);
+    let pages = page_count(level_count);
+    page.0 = event.0.min(pages - 1);
+
+    for entity in grid {
+        commands.entity(entity).despawn();
+    }
+
+    let first = page.0 * LEVELS_PER_PAGE;
+    let last = (first + LEVELS_PER_PAGE).min(level_count);
+
+    commands.entity(hud.0).with_children(|parent| {
+        for (row, start) in (first..last).step_by(LEVELS_PER_ROW).enumerate() {
+            let end = (start + LEVELS_PER_ROW).min(last);
+            // Center a short row under the full ones
+            let left = 120.0 + (LEVELS_PER_ROW - (end - start)) as f32 * 32.0;
+
+            parent
+                .spawn((
+                    StateScoped(AppState::LeaderboardSelection),
+                    LeaderboardSelectionGrid,
                     Node {
-                        width: Val::Px(64.0),
-                        height: Val::Px(64.0),
-                        align_content: AlignContent::Center,
-                        align_items: AlignItems::Center,
-                        justify_content: JustifyContent::Center,
-                        justify_items: JustifyItems::Center,
-                        ..default()
-                    },
-                    ImageNode {
-                        image: image_assets.leaves.clone(),
-                        texture_atlas: Some(TextureAtlas {
-                            layout: image_assets.leaves_layout.clone(),
-                            index: n,
-                        }),
+                        position_type: PositionType::Absolute,
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        width: Val::Percent(100.0),
+                        top: Val::Px(100.0 + row as f32 * 100.0),
+                        left: Val::Px(left),
                         ..default()
                     },
                 ))
-                .with_child((
-                    TextColor(LIGHT_COLOR),
-                    TextShadow {
-                        offset: Vec2::splat(3.0),
-                        color: DARK_COLOR,
+                .with_children(|p| {
+                    for n in start..end {
+                        let lvl = (n + 1).to_string();
+                        p.spawn((
+                            LeaderboardLevel(n),
+                            Node {
+                                width: Val::Px(64.0),
+                                height: Val::Px(64.0),
+                                border: UiRect::all(Val::Px(2.0)),
+                                align_content: AlignContent::Center,
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                justify_items: JustifyItems::Center,
+                                ..default()
+                            },
+                            BorderColor(CLEAR_COLOR),
+                            BorderRadius::all(Val::Px(8.0)),
+                            ImageNode {
+                                image: image_assets.leaves.clone(),
+                                texture_atlas: Some(TextureAtlas {
+                                    layout: image_assets.leaves_layout.clone(),
+                                    index: n % LEAVES_FRAME_COUNT,
+                                }),
+                                ..default()
+                            },
+                        ))
+                        .with_child((
+                            TextColor(LIGHT_COLOR),
+                            TextShadow {
+                                offset: Vec2::splat(3.0),
+                                color: DARK_COLOR,
+                            },
+                            TextFont::from_font(BODY_FONT)
+                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 14.)
+                                .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+                            Pickable::IGNORE,
+                            Text::new(lvl),
+                        ))
+                        .observe(set_leaderboard_cursor_on_hover)
+                        .observe(set_leaderboard_level_on_click);
+                    }
+                });
+        }
+
+        if pages > 1 {
+            parent
+                .spawn((
+                    StateScoped(AppState::LeaderboardSelection),
+                    LeaderboardSelectionGrid,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        width: Val::Percent(100.0),
+                        top: Val::Px(320.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(20.0),
+                        ..default()
                     },
-                    TextFont::from_font(BODY_FONT)
-                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 14.)
-                        .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
-                    Pickable::IGNORE,
-                    Text::new(lvl),
                 ))
-                .observe(set_leaderboard_level_on_click);
-            });
+                .with_children(|p| {
+                    if page.0 > 0 {
+                        p.spawn(page_button("<", -1)).observe(change_page_on_click);
+                    }
+                    p.spawn((
+                        TextColor(LIGHT_COLOR),
+                        TextFont::from_font(BODY_FONT)
+                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+                        Text(format!("Page {}/{}", page.0 + 1, pages)),
+                    ));
+                    if page.0 + 1 < pages {
+                        p.spawn(page_button(">", 1)).observe(change_page_on_click);
+                    }
+                });
+        }
     });
 }
 
+fn page_button(label: &str, step: isize) -> impl Bundle {
+    (
+        LeaderboardPageButton(step),
+        Node {
+            width: Val::Px(40.0),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        Pickable::default(),
+        Text::default(),
+        BackgroundColor(DARK_COLOR),
+        TextLayout::default().with_justify(JustifyText::Center),
+        children![(
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT)
+                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
+                .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+            Pickable::IGNORE,
+            TextSpan::new(label),
+        )],
+    )
+}
+
+fn change_page_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    buttons: Query<&LeaderboardPageButton>,
+    page: Res<LeaderboardSelectionPage>,
+    levels: Res<Assets<LevelLex>>,
+    mut cursor: ResMut<LeaderboardSelectionCursor>,
+) {
+    let Ok(button) = buttons.get(trigger.target) else {
+        return;
+    };
+
+    let next_page = page.0.saturating_add_signed(button.0);
+    cursor.0 = (next_page * LEVELS_PER_PAGE).min(level_count(&levels).saturating_sub(1));
+    commands.send_event(ChangeLeaderboardPage(next_page));
+}
+
+fn set_leaderboard_cursor_on_hover(
+    trigger: Trigger<Pointer<Over>>,
+    button: Query<&LeaderboardLevel>,
+    mut cursor: ResMut<LeaderboardSelectionCursor>,
+) {
+    if let Ok(selection) = button.get(trigger.target) {
+        cursor.0 = selection.0;
+    }
+}
+
+fn leaderboard_selection_navigation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut interaction_rate_limit: ResMut<InteractionRateLimit>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    levels: Res<Assets<LevelLex>>,
+    page: Res<LeaderboardSelectionPage>,
+    mut cursor: ResMut<LeaderboardSelectionCursor>,
+    mut leaderboard_level_selected: ResMut<LeaderboardLevelSelected>,
+) {
+    let level_count = level_count(&levels);
+    if level_count == 0 {
+        return;
+    }
+
+    let pressed = |action| bindings.pressed(action, &keyboard_input, &gamepads);
+    let (left, right, up, down) = (
+        pressed(Action::Left),
+        pressed(Action::Right),
+        pressed(Action::Up),
+        pressed(Action::Down),
+    );
+    let confirm = bindings.just_pressed(Action::Confirm, &keyboard_input, &gamepads);
+    let back = bindings.just_pressed(Action::Back, &keyboard_input, &gamepads);
+
+    if back {
+        commands.send_event(SceneChange(AppState::Menu));
+        return;
+    }
+
+    if confirm {
+        leaderboard_level_selected.0 = Some(cursor.0);
+        commands.send_event(GetHighScore);
+        return;
+    }
+
+    interaction_rate_limit.0.tick(time.delta());
+    if !interaction_rate_limit.0.finished() || !(left || right || up || down) {
+        return;
+    }
+    interaction_rate_limit.0.reset();
+
+    let last = level_count - 1;
+    let position = cursor.0.min(last);
+    cursor.0 = if left {
+        position.saturating_sub(1)
+    } else if right {
+        (position + 1).min(last)
+    } else if up {
+        position.checked_sub(LEVELS_PER_ROW).unwrap_or(position)
+    } else if position + LEVELS_PER_ROW <= last {
+        position + LEVELS_PER_ROW
+    } else {
+        position
+    };
+
+    let cursor_page = cursor.0 / LEVELS_PER_PAGE;
+    if cursor_page != page.0 {
+        commands.send_event(ChangeLeaderboardPage(cursor_page));
+    }
+}
+
+fn highlight_leaderboard_selection(
+    cursor: Res<LeaderboardSelectionCursor>,
+    mut tiles: Query<(&LeaderboardLevel, &mut BorderColor)>,
+) {
+    for (level, mut border) in tiles.iter_mut() {
+        border.0 = if level.0 == cursor.0 {
+            LIGHT_COLOR
+        } else {
+            CLEAR_COLOR
+        };
+    }
+}
+
 fn spacer() -> impl Bundle {
     (
         TextColor(LIGHT_COLOR),
@@ -289,6 +482,121 @@ pub fn set_leaderboard_level_on_click(
     };
 }
 
+// Which board the leaderboard screen shows. Every submitted score counts towards all
+// three; the month and week boards start over each season.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardSeason {
+    #[default]
+    AllTime,
+    Month,
+    Week,
+}
+
+impl LeaderboardSeason {
+    const ALL: [Self; 3] = [Self::AllTime, Self::Month, Self::Week];
+
+    fn query(&self) -> &'static str {
+        match self {
+            Self::AllTime => "all",
+            Self::Month => "month",
+            Self::Week => "week",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::AllTime => "All Time",
+            Self::Month => "This Month",
+            Self::Week => "This Week",
+        }
+    }
+
+    fn step(&self, step: isize) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|season| season == self)
+            .unwrap_or(0);
+        let count = Self::ALL.len() as isize;
+        Self::ALL[(index as isize + step).rem_euclid(count) as usize]
+    }
+}
+
+#[derive(Component)]
+pub struct LeaderboardSeasonTab(pub LeaderboardSeason);
+
+#[derive(Event)]
+pub struct ChangeLeaderboardSeason(pub LeaderboardSeason);
+
+// Global boards, or the ones for the group code entered in the menu
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardScope {
+    #[default]
+    Global,
+    Group,
+}
+
+impl LeaderboardScope {
+    // The group code to ask the leaderboard for, if any
+    fn group(self, leaderboard_group: &LeaderboardGroup) -> Option<&str> {
+        match self {
+            Self::Global => None,
+            Self::Group => leaderboard_group.0.as_deref(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct LeaderboardScopeTab(pub LeaderboardScope);
+
+#[derive(Event)]
+pub struct ChangeLeaderboardScope(pub LeaderboardScope);
+
+// The boards for typed runs, or for clicked ones, which are ranked apart. Set to the
+// mode a run was played in when it's posted, and switched from the leaderboard screen.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardMode {
+    #[default]
+    Typed,
+    Click,
+}
+
+impl LeaderboardMode {
+    fn played(click_mode: &ClickMode) -> Self {
+        match click_mode.0 {
+            true => Self::Click,
+            false => Self::Typed,
+        }
+    }
+
+    // The mode to ask the leaderboard for, typed runs are the default
+    fn query(self) -> Option<&'static str> {
+        match self {
+            Self::Typed => None,
+            Self::Click => Some("click"),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Typed => "Typed",
+            Self::Click => "Clicked",
+        }
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            Self::Typed => Self::Click,
+            Self::Click => Self::Typed,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct LeaderboardModeTab;
+
+#[derive(Event)]
+pub struct ChangeLeaderboardMode(pub LeaderboardMode);
+
 #[derive(Event)]
 pub struct GetHighScore;
 
@@ -298,6 +606,21 @@ pub struct PostHighScore;
 #[derive(Deserialize, Debug)]
 pub struct LeaderboardOutput {
     leaderboard: Vec<HighScoreData>,
+    rank: Option<HighScoreRankData>,
+    submission: Option<HighScoreSubmissionData>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HighScoreSubmissionData {
+    personal_best: bool,
+    previous: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HighScoreRankData {
+    name: String,
+    score: u32,
+    rank: usize,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -322,38 +645,179 @@ pub struct RenderHighScores;
 #[derive(Resource, Default, Debug)]
 pub struct HighScores(pub Vec<HighScoreData>);
 
+#[derive(Resource, Default, Debug)]
+pub struct HighScoreRank(pub Option<HighScoreRankData>);
+
+#[derive(Resource, Default, Debug)]
+pub struct HighScoreSubmission(pub Option<HighScoreSubmissionData>);
+
+#[derive(Component)]
+pub struct HighScoreSubmissionDisplay;
+
+impl HighScoreRank {
+    // Only shown when the leaderboard doesn't already list the player
+    fn display(&self, leaders: &[&HighScoreData]) -> Option<String> {
+        let rank = self.0.as_ref()?;
+        if leaders.iter().any(|data| data.name == rank.name) {
+            return None;
+        }
+        let name = rank.name.chars().take(NAME_MAX_LEN).collect::<String>();
+        Some(format!("#{} - {}: {}", rank.rank, name, rank.score))
+    }
+}
+
+// GET /scores/{level}, with the player's rank when a name is given
+fn scores_request(
+    level_id: usize,
+    season: LeaderboardSeason,
+    group: Option<&str>,
+    mode: Option<&str>,
+    name: Option<&str>,
+) -> Option<HttpRequest> {
+    let mut url = format!(
+        "{}/scores/{}?limit={}&season={}",
+        LEADERBOARD_URL,
+        level_id,
+        LEADERBOARD_LIMIT,
+        season.query()
+    );
+    if let Some(group) = group {
+        url.push_str(&format!(
+            "&group={}",
+            utf8_percent_encode(group, NON_ALPHANUMERIC)
+        ));
+    }
+    if let Some(mode) = mode {
+        url.push_str(&format!("&mode={}", mode));
+    }
+    if let Some(name) = name.filter(|name| !name.is_empty()) {
+        url.push_str(&format!(
+            "&name={}",
+            utf8_percent_encode(name, NON_ALPHANUMERIC)
+        ));
+    }
+
+    HttpClient::new()
+        .get(url)
+        .try_build()
+        .map_err(|e| error!(?e))
+        .ok()
+}
+
+// The seed and input log the lambda replays the score from, signed so it can't be
+// hand written. The message format must match `RunSummary::message` in the lambda.
+fn run_summary(
+    name: &str,
+    group: Option<&str>,
+    mode: Option<&str>,
+    score: u32,
+    run_record: &RunRecord,
+) -> serde_json::Value {
+    let times = run_record
+        .inputs
+        .times
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut message = format!(
+        "{}|{}|{}|{}|{}|{}",
+        run_record.level, name, run_record.seed, score, run_record.inputs.keys, times
+    );
+    if let Some(mode) = mode {
+        message.push_str(&format!("|{}", mode));
+    }
+    if let Some(group) = group {
+        message.push_str(&format!("|group:{}", group));
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(LEADERBOARD_SIGNING_KEY.as_bytes())
+        .expect("hmac accepts any key size");
+    mac.update(message.as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    serde_json::json!({
+        "level": run_record.level,
+        "name": name,
+        "seed": run_record.seed,
+        "group": group,
+        "mode": mode,
+        "keys": run_record.inputs.keys,
+        "times": run_record.inputs.times,
+        "score": score,
+        "signature": signature,
+    })
+}
+
 pub fn post_high_score(
-    mut commands: Commands,
     mut ev_request: EventWriter<HttpRequest>,
     leaderboard_name: Res<LeaderboardName>,
     leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    leaderboard_group: Res<LeaderboardGroup>,
+    season: Res<LeaderboardSeason>,
+    scope: Res<LeaderboardScope>,
     player_score: Res<PlayerScore>,
+    run_record: Res<RunRecord>,
+    hot_seat: Res<HotSeat>,
+    click_mode: Res<ClickMode>,
+    mut leaderboard_mode: ResMut<LeaderboardMode>,
+    mut high_score_rank: ResMut<HighScoreRank>,
+    mut high_score_submission: ResMut<HighScoreSubmission>,
 ) {
     info!("posting high score");
+    high_score_rank.0 = None;
+    high_score_submission.0 = None;
 
-    // The only requirement in this function is the level id. At minimum, this function
-    // will fetch the latest scores. If a name and score are provided, the called
-    // function will process this and add the new scores to teh database, and then
-    // return the latest scores.
     let Some(level_id) = leaderboard_level_selected.0 else {
         warn!("No level_id");
         return;
     };
 
-    let name = match &leaderboard_name.0 {
-        Some(name) => name.clone(),
-        None => String::new(),
-    };
+    let group = scope.group(&leaderboard_group);
+    // The run goes on its own mode's boards, so that's the board shown after
+    *leaderboard_mode = LeaderboardMode::played(&click_mode);
+    let mode = leaderboard_mode.query();
 
-    let score = match player_score.0.get(&level_id) {
-        Some(s) => *s,
-        None => 0,
+    // Without a name or a recorded run there is nothing to submit, so just fetch the board.
+    // Hot-seat turns are posted under the player's own name.
+    let name = match hot_seat.is_active() {
+        true => hot_seat.leaderboard_name(),
+        false => leaderboard_name.0.as_deref(),
+    }
+    .filter(|name| !name.is_empty());
+    let score = player_score
+        .0
+        .get(&level_id)
+        .filter(|_| run_record.level == level_id);
+    let (Some(name), Some(score)) = (name, score) else {
+        if let Some(request) = scores_request(level_id, *season, group, mode, None) {
+            ev_request.write(request);
+        }
+        return;
     };
 
-    let client = HttpClient::new();
-    match client
-        .post(LEADERBOARD_URL)
-        .json(&serde_json::json!({"level": level_id, "name": name, "score": score, }))
+    let mut url = format!(
+        "{}/scores?limit={}&season={}",
+        LEADERBOARD_URL,
+        LEADERBOARD_LIMIT,
+        season.query()
+    );
+    if let Some(group) = group {
+        url.push_str(&format!(
+            "&group={}",
+            utf8_percent_encode(group, NON_ALPHANUMERIC)
+        ));
+    }
+
+    match HttpClient::new()
+        .post(url)
+        .json(&run_summary(
+            name,
+            leaderboard_group.0.as_deref(),
+            mode,
+            *score,
+            &run_record,
+        ))
         .try_build()
     {
         Ok(request) => {
@@ -366,41 +830,63 @@ pub fn post_high_score(
 pub fn get_high_score(
     mut commands: Commands,
     mut ev_request: EventWriter<HttpRequest>,
+    leaderboard_name: Res<LeaderboardName>,
     leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    leaderboard_group: Res<LeaderboardGroup>,
+    season: Res<LeaderboardSeason>,
+    scope: Res<LeaderboardScope>,
+    leaderboard_mode: Res<LeaderboardMode>,
+    mut high_score_rank: ResMut<HighScoreRank>,
 ) {
-    // The only requirement in this function is the level id. At minimum, this function
-    // will fetch the latest scores. If a name and score are provided, the called
-    // function will process this and add the new scores to teh database, and then
-    // return the latest scores.
+    high_score_rank.0 = None;
+
     let Some(level_id) = leaderboard_level_selected.0 else {
         warn!("No level_id");
         return;
     };
 
-    let client = HttpClient::new();
-    match client
-        .post(LEADERBOARD_URL)
-        .json(&serde_json::json!({"level": level_id, "name": "", "score": 0, }))
-        .try_build()
-    {
-        Ok(request) => {
-            ev_request.write(request);
-        }
-        Err(e) => error!(?e),
+    let group = scope.group(&leaderboard_group);
+    if let Some(request) = scores_request(
+        level_id,
+        *season,
+        group,
+        leaderboard_mode.query(),
+        leaderboard_name.0.as_deref(),
+    ) {
+        ev_request.write(request);
     }
 
     commands.send_event(SceneChange(AppState::Leaderboard));
 }
 
+#[derive(Deserialize, Debug)]
+struct LeaderboardError {
+    error: String,
+}
+
 fn handle_response(
     mut ev_resp: EventReader<HttpResponse>,
     mut high_score_data: ResMut<HighScores>,
+    mut high_score_rank: ResMut<HighScoreRank>,
+    mut high_score_submission: ResMut<HighScoreSubmission>,
 ) {
     for response in ev_resp.read() {
+        if !response.ok {
+            match response.json::<LeaderboardError>() {
+                Ok(body) => warn!("Leaderboard error {}: {}", response.status, body.error),
+                Err(_) => warn!("Leaderboard error {}", response.status),
+            }
+            continue;
+        }
+
         if let Ok(data) = response.json::<LeaderboardOutput>() {
             let high_scores = data.leaderboard;
 
             high_score_data.0 = high_scores;
+            high_score_rank.0 = data.rank;
+            if data.submission.is_some() {
+                high_score_submission.0 = data.submission;
+            }
         };
     }
 }
@@ -411,8 +897,63 @@ fn handle_error(mut ev_error: EventReader<HttpResponseError>) {
     }
 }
 
-fn setup_leaderboard(mut commands: Commands, hud: Res<Hud>) {
+fn setup_leaderboard(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    leaderboard_group: Res<LeaderboardGroup>,
+) {
     commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::Leaderboard),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(15.0),
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                for season in LeaderboardSeason::ALL {
+                    p.spawn(season_tab(season))
+                        .observe(set_leaderboard_season_on_click);
+                }
+            });
+
+        parent
+            .spawn((
+                StateScoped(AppState::Leaderboard),
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    top: Val::Px(425.0),
+                    left: Val::Px(20.0),
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+            ))
+            .with_children(|p| {
+                // One tab that flips between the modes, its label shows the current one
+                p.spawn(tab(LeaderboardModeTab, ""))
+                    .observe(toggle_leaderboard_mode_on_click);
+
+                // Only worth switching to when there's a group to show
+                if let Some(group) = &leaderboard_group.0 {
+                    p.spawn(tab(LeaderboardScopeTab(LeaderboardScope::Global), "Global"))
+                        .observe(set_leaderboard_scope_on_click);
+                    p.spawn(tab(
+                        LeaderboardScopeTab(LeaderboardScope::Group),
+                        format!("Group {}", group),
+                    ))
+                    .observe(set_leaderboard_scope_on_click);
+                }
+            });
+
         parent
             .spawn((
                 StateScoped(AppState::LeaderboardSelection),
@@ -489,6 +1030,215 @@ fn setup_leaderboard(mut commands: Commands, hud: Res<Hud>) {
     });
 }
 
+fn season_tab(season: LeaderboardSeason) -> impl Bundle {
+    tab(LeaderboardSeasonTab(season), season.label())
+}
+
+fn tab(marker: impl Component, label: impl Into<String>) -> impl Bundle {
+    (
+        marker,
+        Node {
+            width: Val::Px(140.0),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(CLEAR_COLOR),
+        BorderRadius::MAX,
+        Pickable::default(),
+        Text::default(),
+        BackgroundColor(DARK_COLOR),
+        TextLayout::default().with_justify(JustifyText::Center),
+        children![(
+            TextColor(LIGHT_COLOR),
+            TextFont::from_font(BODY_FONT)
+                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
+                .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+            Pickable::IGNORE,
+            TextSpan::new(label),
+        )],
+    )
+}
+
+fn set_leaderboard_season_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    tabs: Query<&LeaderboardSeasonTab>,
+) {
+    if let Ok(tab) = tabs.get(trigger.target) {
+        commands.send_event(ChangeLeaderboardSeason(tab.0));
+    }
+}
+
+fn leaderboard_season_navigation(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    season: Res<LeaderboardSeason>,
+) {
+    let gamepad_pressed =
+        |button: GamepadButton| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+
+    let step = if keyboard_input.just_pressed(bindings.key(Action::Left))
+        || gamepad_pressed(GamepadButton::LeftTrigger)
+    {
+        -1
+    } else if keyboard_input.just_pressed(bindings.key(Action::Right))
+        || gamepad_pressed(GamepadButton::RightTrigger)
+    {
+        1
+    } else {
+        return;
+    };
+
+    commands.send_event(ChangeLeaderboardSeason(season.step(step)));
+}
+
+fn set_leaderboard_scope_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    tabs: Query<&LeaderboardScopeTab>,
+) {
+    if let Ok(tab) = tabs.get(trigger.target) {
+        commands.send_event(ChangeLeaderboardScope(tab.0));
+    }
+}
+
+fn leaderboard_scope_navigation(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    leaderboard_group: Res<LeaderboardGroup>,
+    scope: Res<LeaderboardScope>,
+) {
+    let toggle = keyboard_input.just_pressed(KeyCode::Tab)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::North));
+    if !toggle || leaderboard_group.0.is_none() {
+        return;
+    }
+
+    commands.send_event(ChangeLeaderboardScope(match *scope {
+        LeaderboardScope::Global => LeaderboardScope::Group,
+        LeaderboardScope::Group => LeaderboardScope::Global,
+    }));
+}
+
+fn toggle_leaderboard_mode_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    leaderboard_mode: Res<LeaderboardMode>,
+) {
+    commands.send_event(ChangeLeaderboardMode(leaderboard_mode.toggled()));
+}
+
+fn leaderboard_mode_navigation(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    leaderboard_mode: Res<LeaderboardMode>,
+) {
+    let toggle = keyboard_input.just_pressed(KeyCode::KeyM)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::West));
+    if toggle {
+        commands.send_event(ChangeLeaderboardMode(leaderboard_mode.toggled()));
+    }
+}
+
+// Swaps the board in place rather than re-entering the leaderboard screen
+fn change_leaderboard_view(
+    mut season_changes: EventReader<ChangeLeaderboardSeason>,
+    mut scope_changes: EventReader<ChangeLeaderboardScope>,
+    mut mode_changes: EventReader<ChangeLeaderboardMode>,
+    mut ev_request: EventWriter<HttpRequest>,
+    leaderboard_name: Res<LeaderboardName>,
+    leaderboard_group: Res<LeaderboardGroup>,
+    leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    mut season: ResMut<LeaderboardSeason>,
+    mut scope: ResMut<LeaderboardScope>,
+    mut leaderboard_mode: ResMut<LeaderboardMode>,
+    mut high_score_data: ResMut<HighScores>,
+    mut high_score_rank: ResMut<HighScoreRank>,
+) {
+    let next_season = season_changes
+        .read()
+        .last()
+        .map_or(*season, |event| event.0);
+    let next_scope = scope_changes.read().last().map_or(*scope, |event| event.0);
+    let next_mode = mode_changes
+        .read()
+        .last()
+        .map_or(*leaderboard_mode, |event| event.0);
+    if *season == next_season && *scope == next_scope && *leaderboard_mode == next_mode {
+        return;
+    }
+    *season = next_season;
+    *scope = next_scope;
+    *leaderboard_mode = next_mode;
+    high_score_data.0.clear();
+    high_score_rank.0 = None;
+
+    let Some(level_id) = leaderboard_level_selected.0 else {
+        return;
+    };
+    let group = scope.group(&leaderboard_group);
+    if let Some(request) = scores_request(
+        level_id,
+        *season,
+        group,
+        leaderboard_mode.query(),
+        leaderboard_name.0.as_deref(),
+    ) {
+        ev_request.write(request);
+    }
+}
+
+fn highlight_leaderboard_season(
+    season: Res<LeaderboardSeason>,
+    mut tabs: Query<(&LeaderboardSeasonTab, &mut BorderColor)>,
+) {
+    for (tab, mut border) in tabs.iter_mut() {
+        border.0 = if tab.0 == *season {
+            LIGHT_COLOR
+        } else {
+            CLEAR_COLOR
+        };
+    }
+}
+
+fn highlight_leaderboard_scope(
+    scope: Res<LeaderboardScope>,
+    mut tabs: Query<(&LeaderboardScopeTab, &mut BorderColor)>,
+) {
+    for (tab, mut border) in tabs.iter_mut() {
+        border.0 = if tab.0 == *scope {
+            LIGHT_COLOR
+        } else {
+            CLEAR_COLOR
+        };
+    }
+}
+
+fn update_leaderboard_mode_tab(
+    leaderboard_mode: Res<LeaderboardMode>,
+    tabs: Query<&Children, With<LeaderboardModeTab>>,
+    mut spans: Query<&mut TextSpan>,
+) {
+    let Some(&child) = tabs.iter().flatten().find(|&&child| spans.contains(child)) else {
+        return;
+    };
+    let Ok(mut span) = spans.get_mut(child) else {
+        return;
+    };
+    let label = format!("{} runs", leaderboard_mode.label());
+    if span.0 != label {
+        span.0 = label;
+    }
+}
+
 fn back_to_leaderboard_selection(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.send_event(SceneChange(AppState::LeaderboardSelection));
 }
@@ -497,9 +1247,28 @@ fn back_to_menu(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.send_event(SceneChange(AppState::Menu));
 }
 
+fn leaderboard_back_navigation(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+) {
+    if bindings.just_pressed(Action::Back, &keyboard_input, &gamepads)
+        || keyboard_input.just_pressed(KeyCode::Backspace)
+    {
+        commands.send_event(SceneChange(AppState::LeaderboardSelection));
+    }
+}
+
 fn update_high_scoreboard(
     high_score_data: Res<HighScores>,
+    high_score_rank: Res<HighScoreRank>,
     leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    leaderboard_group: Res<LeaderboardGroup>,
+    season: Res<LeaderboardSeason>,
+    scope: Res<LeaderboardScope>,
+    leaderboard_mode: Res<LeaderboardMode>,
+    levels: Res<Assets<LevelLex>>,
     mut high_scoreboard_level: Query<&mut Text, With<HighScoreboardLevel>>,
     mut high_scoreboard: Query<&mut Text, (With<HighScoreboard>, Without<HighScoreboardLevel>)>,
 ) {
@@ -512,7 +1281,18 @@ fn update_high_scoreboard(
 
     let level_id = match leaderboard_level_selected.0 {
         Some(level_id) => {
-            header_text.0 = format!("High Scores - Level {}\n----------------\n", level_id + 1);
+            let mut board = match scope.group(&leaderboard_group) {
+                Some(group) => format!("{} ({})", season.label(), group),
+                None => season.label().to_string(),
+            };
+            if *leaderboard_mode == LeaderboardMode::Click {
+                board.push_str(" - Click");
+            }
+            header_text.0 = format!(
+                "High Scores - Level {} - {}\n----------------\n",
+                level_id + 1,
+                board
+            );
             level_id
         }
         None => {
@@ -526,26 +1306,32 @@ fn update_high_scoreboard(
         .iter()
         .filter(|data| data.level == level_id)
         .collect::<Vec<_>>();
-    leaders.sort_by(|a, b| a.score.cmp(&b.score));
+    let scoring = level_scoring(&levels, level_id);
+    leaders.sort_by(|a, b| scoring.compare(a.score, b.score));
+    leaders.truncate(10);
 
-    let display_data = leaders
+    let mut display_data = leaders
         .iter()
         .enumerate()
-        .filter(|(_, data)| data.level == level_id)
-        .filter(|(idx, _data)| *idx < 10)
         .map(|(idx, data)| {
-            let name = data.name.chars().take(16).collect::<String>();
+            let name = data.name.chars().take(NAME_MAX_LEN).collect::<String>();
             format!("#{} - {}: {}", idx + 1, name, data.score)
         })
-        .collect::<Vec<_>>()
-        .join("\n\n");
+        .collect::<Vec<_>>();
+
+    if let Some(rank) = high_score_rank.display(&leaders) {
+        display_data.push("...".into());
+        display_data.push(rank);
+    }
 
-    text.0 = display_data;
+    text.0 = display_data.join("\n\n");
 }
 
 pub fn update_high_scoreboard_top_five(
     high_score_data: Res<HighScores>,
+    high_score_rank: Res<HighScoreRank>,
     leaderboard_level_selected: Res<LeaderboardLevelSelected>,
+    levels: Res<Assets<LevelLex>>,
     mut high_scoreboard: Query<&mut Text, With<HighScoreboardTopFive>>,
 ) {
     let Some(level_id) = leaderboard_level_selected.0 else {
@@ -561,19 +1347,48 @@ pub fn update_high_scoreboard_top_five(
         .iter()
         .filter(|data| data.level == level_id)
         .collect::<Vec<_>>();
-    leaders.sort_by(|a, b| a.score.cmp(&b.score));
+    let scoring = level_scoring(&levels, level_id);
+    leaders.sort_by(|a, b| scoring.compare(a.score, b.score));
+    leaders.truncate(5);
 
-    let display_data = leaders
+    let mut display_data = leaders
         .iter()
         .enumerate()
-        .filter(|(_, data)| data.level == level_id)
-        .filter(|(idx, _data)| *idx < 5)
         .map(|(idx, data)| {
-            let name = data.name.chars().take(16).collect::<String>();
+            let name = data.name.chars().take(NAME_MAX_LEN).collect::<String>();
             format!("#{} - {}: {}", idx + 1, name, data.score)
         })
-        .collect::<Vec<_>>()
-        .join("\n\n");
+        .collect::<Vec<_>>();
+
+    if let Some(rank) = high_score_rank.display(&leaders) {
+        display_data.push("...".into());
+        display_data.push(rank);
+    }
+
+    text.0 = display_data.join("\n\n");
+}
+
+pub fn update_high_score_submission(
+    high_score_submission: Res<HighScoreSubmission>,
+    mut display: Query<&mut Text, With<HighScoreSubmissionDisplay>>,
+) {
+    let Ok(mut text) = display.single_mut() else {
+        return;
+    };
 
-    text.0 = display_data;
+    text.0 = match &high_score_submission.0 {
+        Some(HighScoreSubmissionData {
+            personal_best: true,
+            previous: Some(previous),
+        }) => format!("New personal best! (was {})", previous),
+        Some(HighScoreSubmissionData {
+            personal_best: true,
+            previous: None,
+        }) => "New personal best!".into(),
+        Some(HighScoreSubmissionData {
+            personal_best: false,
+            previous: Some(previous),
+        }) => format!("Your best is still {}", previous),
+        _ => String::new(),
+    };
 }
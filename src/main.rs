@@ -1,6 +1,7 @@
 mod app;
 mod assets;
 mod camera;
+mod controls;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod game;
@@ -2,14 +2,32 @@ use super::ChangeMenu;
 use crate::app::AppState;
 use crate::app::DisplayLanguage;
 
-use crate::game::SceneChange;
+use crate::game::{ClickMode, SceneChange};
 
 use bevy::prelude::*;
 
 pub fn start_game(mut commands: Commands) {
+    commands.insert_resource(ClickMode(false));
     commands.send_event(SceneChange(AppState::Game));
 }
 
+pub fn start_click_mode(mut commands: Commands) {
+    commands.insert_resource(ClickMode(true));
+    commands.send_event(SceneChange(AppState::Game));
+}
+
+pub fn start_hot_seat(mut commands: Commands) {
+    commands.send_event(SceneChange(AppState::HotSeatSetup));
+}
+
+pub fn start_split_screen(mut commands: Commands) {
+    commands.send_event(SceneChange(AppState::SplitScreen));
+}
+
+pub fn start_versus(mut commands: Commands) {
+    commands.send_event(SceneChange(AppState::VersusLobby));
+}
+
 pub fn language_selection(
     mut display_language: ResMut<DisplayLanguage>,
     language: impl Into<String>,
@@ -28,3 +46,7 @@ pub fn show_credits(mut commands: Commands) {
 pub fn show_leaderboard(mut commands: Commands) {
     commands.send_event(SceneChange(AppState::LeaderboardSelection));
 }
+
+pub fn show_controls(mut commands: Commands) {
+    commands.send_event(SceneChange(AppState::Controls));
+}
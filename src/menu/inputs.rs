@@ -4,8 +4,8 @@ use super::{
 use crate::app::AppState;
 use crate::app::DisplayLanguage;
 use crate::app::InteractionRateLimit;
+use crate::controls::{Action, Bindings, action_just_pressed};
 
-use bevy::input::common_conditions::input_just_pressed;
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -18,41 +18,44 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        keyboard_selection.run_if(input_just_pressed(KeyCode::Enter).and(in_state(AppState::Menu))),
+        keyboard_selection
+            .run_if(action_just_pressed(Action::Confirm).and(in_state(AppState::Menu))),
+    );
+
+    app.add_systems(
+        Update,
+        menu_back.run_if(action_just_pressed(Action::Back).and(in_state(AppState::Menu))),
     );
 }
 
+// Every menu is one step from the main menu
+fn menu_back(active_menu: Res<ActiveMenu>, change_menu: EventWriter<ChangeMenu>) {
+    let Some(menu) = &active_menu.opt else {
+        return;
+    };
+    if menu.id != "main menu" {
+        actions::menu_selection(change_menu, "main menu");
+    }
+}
+
 fn keyboard_menu_selection_system(
     time: Res<Time>,
     mut interaction_rate_limit: ResMut<InteractionRateLimit>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
     dialog_message: ResMut<ActiveMenu>,
     mut current_selection: ResMut<CurrentSelection>,
 ) {
-    let (_right, _left, _, up, down, _pause) = match gamepads.iter().next() {
+    let (up, down) = match gamepads.iter().next() {
         Some(gamepad) => {
-            let left_stick_x = gamepad.get(GamepadAxis::LeftStickX).unwrap();
             let left_stick_y = gamepad.get(GamepadAxis::LeftStickY).unwrap();
-
-            (
-                left_stick_x > 0.075,  //right
-                left_stick_x < -0.075, //left
-                gamepad.any_just_pressed([
-                    GamepadButton::North,
-                    GamepadButton::South,
-                    GamepadButton::East,
-                    GamepadButton::West,
-                ]),
-                left_stick_y > 0.075, //up
-                left_stick_y < -0.75, //down
-                gamepad.just_pressed(GamepadButton::Start),
-            )
+            (left_stick_y > 0.075, left_stick_y < -0.075)
         }
-        None => (false, false, false, false, false, false),
+        None => (false, false),
     };
-    let up_key_pressed = up || keyboard_input.any_pressed([KeyCode::ArrowUp]);
-    let down_key_pressed = down || keyboard_input.any_pressed([KeyCode::ArrowDown]);
+    let up_key_pressed = up || bindings.pressed(Action::Up, &keyboard_input, &gamepads);
+    let down_key_pressed = down || bindings.pressed(Action::Down, &keyboard_input, &gamepads);
 
     let dialog = match &dialog_message.opt {
         Some(d) => d,
@@ -130,12 +133,27 @@ fn keyboard_selection(
             "start_game" => {
                 actions::start_game(commands);
             }
+            "click_mode" => {
+                actions::start_click_mode(commands);
+            }
+            "hot_seat" => {
+                actions::start_hot_seat(commands);
+            }
+            "split_screen" => {
+                actions::start_split_screen(commands);
+            }
+            "versus" => {
+                actions::start_versus(commands);
+            }
             "show_credits" => {
                 actions::show_credits(commands);
             }
             "show_leaderboard" => {
                 actions::show_leaderboard(commands);
             }
+            "show_controls" => {
+                actions::show_controls(commands);
+            }
 
             "english" | "spanish" => {
                 actions::language_selection(display_language, action);
@@ -179,6 +197,22 @@ pub fn click_start_game(_: Trigger<Pointer<Click>>, commands: Commands) {
     actions::start_game(commands);
 }
 
+pub fn click_start_click_mode(_: Trigger<Pointer<Click>>, commands: Commands) {
+    actions::start_click_mode(commands);
+}
+
+pub fn click_start_hot_seat(_: Trigger<Pointer<Click>>, commands: Commands) {
+    actions::start_hot_seat(commands);
+}
+
+pub fn click_start_split_screen(_: Trigger<Pointer<Click>>, commands: Commands) {
+    actions::start_split_screen(commands);
+}
+
+pub fn click_start_versus(_: Trigger<Pointer<Click>>, commands: Commands) {
+    actions::start_versus(commands);
+}
+
 pub fn click_show_credits(_: Trigger<Pointer<Click>>, commands: Commands) {
     actions::show_credits(commands);
 }
@@ -187,6 +221,10 @@ pub fn click_show_leaderboard(_: Trigger<Pointer<Click>>, commands: Commands) {
     actions::show_leaderboard(commands);
 }
 
+pub fn click_show_controls(_: Trigger<Pointer<Click>>, commands: Commands) {
+    actions::show_controls(commands);
+}
+
 pub fn click_language_selection(
     trigger: Trigger<Pointer<Click>>,
     display_language: ResMut<DisplayLanguage>,
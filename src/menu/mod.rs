@@ -13,10 +13,14 @@ use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
 use bevy_aspect_ratio_mask::Hud;
 use bevy_simple_text_input::TextInput;
+use bevy_simple_text_input::TextInputInactive;
+use bevy_simple_text_input::TextInputPlaceholder;
 use bevy_simple_text_input::TextInputPlugin;
+use bevy_simple_text_input::TextInputSettings;
 use bevy_simple_text_input::TextInputTextColor;
 use bevy_simple_text_input::TextInputTextFont;
 use bevy_simple_text_input::TextInputValue;
+use weed_core::{NameRules, group_code};
 
 mod actions;
 mod inputs;
@@ -30,9 +34,15 @@ impl Plugin for Menu {
 
         app.add_plugins((TextInputPlugin))
             .insert_resource(LeaderboardName::default())
+            .insert_resource(LeaderboardGroup::default())
             .add_systems(
                 Update,
-                leaderboard_name.run_if(in_state(AppState::Menu).and(on_event::<KeyboardInput>)),
+                (leaderboard_name, leaderboard_group)
+                    .run_if(in_state(AppState::Menu).and(on_event::<KeyboardInput>)),
+            )
+            .add_systems(
+                Update,
+                cycle_text_input_focus.run_if(in_state(AppState::Menu)),
             );
 
         app.add_event::<ChangeMenu>();
@@ -123,6 +133,7 @@ fn change_menu(
     hud: Res<Hud>,
     image_assets: Res<ImageAssets>,
     mut leaderboard_name: ResMut<LeaderboardName>,
+    leaderboard_group: Res<LeaderboardGroup>,
 ) {
     info!("Spawning menu");
     let hud_entity = hud.0;
@@ -204,9 +215,9 @@ fn change_menu(
                         DialogDisplay(dialog.id.clone()),
                         Node {
                             position_type: PositionType::Absolute,
-                            width: Val::Px(220.0),
+                            width: Val::Px(170.0),
                             height: Val::Px(30.0),
-                            left: Val::Px(400.0),
+                            left: Val::Px(290.0),
                             top: Val::Px(436.0),
                             // border: UiRect::all(Val::Px(5.0)),
                             padding: UiRect::all(Val::Px(7.0)),
@@ -225,9 +236,9 @@ fn change_menu(
                         DialogDisplay(dialog.id.clone()),
                         Node {
                             position_type: PositionType::Absolute,
-                            width: Val::Px(220.0),
+                            width: Val::Px(170.0),
                             height: Val::Px(30.0),
-                            left: Val::Px(400.0),
+                            left: Val::Px(290.0),
                             top: Val::Px(455.0),
                             // border: UiRect::all(Val::Px(5.0)),
                             padding: UiRect::all(Val::Px(7.0)),
@@ -249,7 +260,7 @@ fn change_menu(
                                 position_type: PositionType::Absolute,
                                 width: Val::Px(30.0),
                                 height: Val::Px(30.0),
-                                left: Val::Px(570.0),
+                                left: Val::Px(455.0),
                                 top: Val::Px(433.0),
                                 // border: UiRect::all(Val::Px(5.0)),
                                 // padding: UiRect::all(Val::Px(7.0)),
@@ -269,29 +280,10 @@ fn change_menu(
                         .observe(remove_name);
                 }
                 None => {
-                    parent.spawn((
-                        StateScoped(AppState::Menu),
-                        DialogDisplay(dialog.id.clone()),
-                        Node {
-                            position_type: PositionType::Absolute,
-                            width: Val::Px(220.0),
-                            height: Val::Px(30.0),
-                            left: Val::Px(410.0),
-                            top: Val::Px(433.0),
-                            // border: UiRect::all(Val::Px(5.0)),
-                            padding: UiRect::all(Val::Px(7.0)),
-                            ..default()
-                        },
-                        BorderColor(LIGHT_COLOR),
-                        BorderRadius::MAX,
-                        BackgroundColor(LIGHT_COLOR),
-                        TextInput,
-                        TextInputTextFont(
-                            TextFont::from_font(BODY_FONT)
-                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                        ),
-                        TextInputTextColor(DARK_COLOR.into()),
-                    ));
+                    parent
+                        .spawn((name_input(), DialogDisplay(dialog.id.clone())))
+                        .observe(focus_text_input);
+                    parent.spawn((name_feedback(), DialogDisplay(dialog.id.clone())));
 
                     parent.spawn((
                         StateScoped(AppState::Menu),
@@ -299,9 +291,9 @@ fn change_menu(
                         DialogDisplay(dialog.id.clone()),
                         Node {
                             position_type: PositionType::Absolute,
-                            width: Val::Px(220.0),
+                            width: Val::Px(170.0),
                             height: Val::Px(30.0),
-                            left: Val::Px(400.0),
+                            left: Val::Px(290.0),
                             top: Val::Px(455.0),
                             // border: UiRect::all(Val::Px(5.0)),
                             padding: UiRect::all(Val::Px(7.0)),
@@ -315,6 +307,15 @@ fn change_menu(
                     ));
                 }
             }
+
+            // Typing goes to the name field first, until there is a name
+            parent
+                .spawn((
+                    group_input(&leaderboard_group, leaderboard_name.0.is_some()),
+                    DialogDisplay(dialog.id.clone()),
+                ))
+                .observe(focus_text_input);
+            parent.spawn((group_feedback(), DialogDisplay(dialog.id.clone())));
         }
 
         parent
@@ -344,12 +345,27 @@ fn change_menu(
                                             "start_game" => {
                                                 button.observe(inputs::click_start_game);
                                             }
+                                            "click_mode" => {
+                                                button.observe(inputs::click_start_click_mode);
+                                            }
+                                            "hot_seat" => {
+                                                button.observe(inputs::click_start_hot_seat);
+                                            }
+                                            "split_screen" => {
+                                                button.observe(inputs::click_start_split_screen);
+                                            }
+                                            "versus" => {
+                                                button.observe(inputs::click_start_versus);
+                                            }
                                             "show_credits" => {
                                                 button.observe(inputs::click_show_credits);
                                             }
                                             "show_leaderboard" => {
                                                 button.observe(inputs::click_show_leaderboard);
                                             }
+                                            "show_controls" => {
+                                                button.observe(inputs::click_show_controls);
+                                            }
 
                                             "english" | "spanish" => {
                                                 button
@@ -387,6 +403,7 @@ fn remove_name(
     _: Trigger<Pointer<Click>>,
     mut leaderboard_name: ResMut<LeaderboardName>,
     query: Query<Entity, With<ShowSetName>>,
+    mut inputs: Query<&mut TextInputInactive, With<GroupInput>>,
     mut commands: Commands,
     hud: Res<Hud>,
 ) {
@@ -396,50 +413,209 @@ fn remove_name(
         commands.entity(entity).despawn();
     }
 
+    // The name field takes the typing back from the group field
+    for mut inactive in &mut inputs {
+        inactive.0 = true;
+    }
+
     commands.entity(hud.0).with_children(|parent| {
-        parent.spawn((
-            StateScoped(AppState::Menu),
-            Node {
-                position_type: PositionType::Absolute,
-                width: Val::Px(220.0),
-                height: Val::Px(30.0),
-                left: Val::Px(410.0),
-                top: Val::Px(433.0),
-                // border: UiRect::all(Val::Px(5.0)),
-                padding: UiRect::all(Val::Px(7.0)),
-                ..default()
-            },
-            BorderColor(LIGHT_COLOR),
-            BorderRadius::MAX,
-            BackgroundColor(LIGHT_COLOR),
-            TextInput,
-            TextInputTextFont(
-                TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-            ),
-            TextInputTextColor(DARK_COLOR.into()),
-        ));
+        parent.spawn(name_input()).observe(focus_text_input);
+        parent.spawn(name_feedback());
     });
 }
 
+#[derive(Component)]
+struct NameInput;
+
+fn name_input() -> impl Bundle {
+    (
+        StateScoped(AppState::Menu),
+        NameInput,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(170.0),
+            height: Val::Px(30.0),
+            left: Val::Px(300.0),
+            top: Val::Px(433.0),
+            // border: UiRect::all(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(7.0)),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        BackgroundColor(LIGHT_COLOR),
+        TextInput,
+        TextInputTextFont(
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+        ),
+        TextInputTextColor(DARK_COLOR.into()),
+    )
+}
+
+// Optional code for a private group board, next to the name field
+#[derive(Component)]
+struct GroupInput;
+
+fn group_input(leaderboard_group: &LeaderboardGroup, active: bool) -> impl Bundle {
+    (
+        StateScoped(AppState::Menu),
+        GroupInput,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(130.0),
+            height: Val::Px(30.0),
+            left: Val::Px(490.0),
+            top: Val::Px(433.0),
+            padding: UiRect::all(Val::Px(7.0)),
+            ..default()
+        },
+        BorderColor(LIGHT_COLOR),
+        BorderRadius::MAX,
+        BackgroundColor(LIGHT_COLOR),
+        TextInput,
+        TextInputInactive(!active),
+        TextInputSettings {
+            retain_on_submit: true,
+            ..default()
+        },
+        TextInputValue(leaderboard_group.0.clone().unwrap_or_default()),
+        TextInputPlaceholder {
+            value: "Group code".into(),
+            ..default()
+        },
+        TextInputTextFont(
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+        ),
+        TextInputTextColor(DARK_COLOR.into()),
+    )
+}
+
+// Only one field takes typing at a time
+fn focus_text_input(
+    trigger: Trigger<Pointer<Click>>,
+    mut inputs: Query<(Entity, &mut TextInputInactive)>,
+) {
+    for (entity, mut inactive) in &mut inputs {
+        inactive.0 = entity != trigger.target;
+    }
+}
+
+fn cycle_text_input_focus(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut inputs: Query<&mut TextInputInactive>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) || inputs.iter().count() < 2 {
+        return;
+    }
+    for mut inactive in &mut inputs {
+        inactive.0 = !inactive.0;
+    }
+}
+
+// Says why the typed name can't be used yet
+#[derive(Component)]
+struct NameFeedback;
+
+fn name_feedback() -> impl Bundle {
+    (
+        StateScoped(AppState::Menu),
+        NameFeedback,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(300.0),
+            top: Val::Px(480.0),
+            ..default()
+        },
+        TextColor(LIGHT_COLOR),
+        TextFont::from_font(BODY_FONT)
+            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 40.)
+            .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+        Text::default(),
+    )
+}
+
+#[derive(Component)]
+struct GroupFeedback;
+
+fn group_feedback() -> impl Bundle {
+    (
+        StateScoped(AppState::Menu),
+        GroupFeedback,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(490.0),
+            top: Val::Px(465.0),
+            ..default()
+        },
+        TextColor(LIGHT_COLOR),
+        TextFont::from_font(BODY_FONT)
+            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 40.)
+            .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
+        Text::default(),
+    )
+}
+
 fn leaderboard_name(
     mut events: EventReader<KeyboardInput>,
-    text_input_query: Query<&TextInputValue>,
+    text_input_query: Query<&TextInputValue, With<NameInput>>,
+    mut feedback: Query<&mut Text, With<NameFeedback>>,
     mut leaderboard_name: ResMut<LeaderboardName>,
+    name_rules: Local<NameRules>,
 ) {
     for event in events.read() {
         if event.key_code == KeyCode::Enter {
             return;
         }
-        leaderboard_name.0 = match text_input_query.single() {
-            Ok(t) => Some(t.0.clone()),
-            Err(_) => None,
+        // No name field means the name is already set
+        let Ok(t) = text_input_query.single() else {
+            continue;
+        };
+
+        // Only a name the leaderboard will accept is kept
+        let (name, message) = match name_rules.validate(&t.0) {
+            Ok(name) => (Some(name), String::new()),
+            Err(_) if t.0.trim().is_empty() => (None, String::new()),
+            Err(e) => (None, e.to_string()),
+        };
+        leaderboard_name.0 = name;
+        if let Ok(mut text) = feedback.single_mut() {
+            text.0 = message;
         }
     }
 }
 
+fn leaderboard_group(
+    mut events: EventReader<KeyboardInput>,
+    text_input_query: Query<&TextInputValue, With<GroupInput>>,
+    mut feedback: Query<&mut Text, With<GroupFeedback>>,
+    mut leaderboard_group: ResMut<LeaderboardGroup>,
+) {
+    if events.read().all(|event| event.key_code == KeyCode::Enter) {
+        return;
+    }
+    let Ok(t) = text_input_query.single() else {
+        return;
+    };
+
+    // Blank means no group, only a valid code is kept
+    let (group, message) = match group_code(&t.0) {
+        Ok(code) => (Some(code), String::new()),
+        Err(_) if t.0.trim().is_empty() => (None, String::new()),
+        Err(e) => (None, e.to_string()),
+    };
+    leaderboard_group.0 = group;
+    if let Ok(mut text) = feedback.single_mut() {
+        text.0 = message;
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct LeaderboardName(pub Option<String>);
 
+// Scores are also posted to this group's boards
+#[derive(Resource, Default)]
+pub struct LeaderboardGroup(pub Option<String>);
+
 impl LeaderboardName {
     pub fn clear(&mut self) {
         self.0 = None;